@@ -0,0 +1,37 @@
+//! The derive macros of wapi (re-exported by the wapi crate, use them from there).
+
+extern crate proc_macro;
+
+mod parse;
+mod pod;
+mod remote_struct;
+
+use proc_macro::TokenStream;
+
+/// Implement `wapi::pod::Pod` for a struct, checking at compile time that the struct is
+/// `#[repr(C)]` (or transparent / packed), that every field is Pod and that there is no padding.
+#[proc_macro_derive(Pod)]
+pub fn derive_pod(input: TokenStream) -> TokenStream {
+    match pod::expand(input) {
+        Ok(tokens) => tokens,
+        Err(error) => error.to_compile_error(),
+    }
+}
+
+/// Implement `wapi::remote_struct::RemoteStruct` for a struct that models a structure of a remote
+/// process, and generate the `<field>_address`, `read_<field>` and `write_<field>` functions of
+/// every field.
+///
+/// Every field needs `#[offset(...)]`, its offset from the address of the struct. The field is
+/// read in place as a Pod value unless:
+/// - `#[pointer]` is set: the field is a pointer to the value, followed when the field is read.
+///   `#[pointer(0x10, 0x8)]` walks more levels like a MultiLevelPointer (the last offset is added
+///   without being followed).
+/// - `#[nested]` is set: the value is another RemoteStruct (no write function is generated).
+#[proc_macro_derive(RemoteStruct, attributes(offset, pointer, nested))]
+pub fn derive_remote_struct(input: TokenStream) -> TokenStream {
+    match remote_struct::expand(input) {
+        Ok(tokens) => tokens,
+        Err(error) => error.to_compile_error(),
+    }
+}
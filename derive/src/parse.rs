@@ -0,0 +1,314 @@
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+/// Represent an error found while expanding a derive macro, reported as a compile error.
+pub struct DeriveError {
+    /// The location of the error.
+    pub span: Span,
+
+    /// The message shown to the user.
+    pub message: String,
+}
+
+impl DeriveError {
+    /// Create an error at the specified location.
+    pub fn new(span: Span, message: impl Into<String>) -> DeriveError {
+        DeriveError {
+            span,
+            message: message.into(),
+        }
+    }
+
+    /// Convert the error into a compile_error! invocation.
+    pub fn to_compile_error(self: &DeriveError) -> TokenStream {
+        let mut message = Literal::string(&self.message);
+        message.set_span(self.span);
+
+        let mut bang = Punct::new('!', Spacing::Alone);
+        bang.set_span(self.span);
+
+        let mut arguments = Group::new(Delimiter::Brace, TokenTree::from(message).into());
+        arguments.set_span(self.span);
+
+        [
+            TokenTree::Ident(Ident::new("compile_error", self.span)),
+            TokenTree::Punct(bang),
+            TokenTree::Group(arguments),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+/// Represent an outer attribute (#[name(arguments)]) of a struct or a field.
+pub struct Attribute {
+    /// The name of the attribute (ex: "repr").
+    pub name: String,
+
+    /// The tokens between the parentheses that follow the name (empty if none).
+    pub arguments: TokenStream,
+
+    /// The location of the attribute.
+    pub span: Span,
+}
+
+/// Represent a field of a struct.
+pub struct Field {
+    /// The name of the field (None for the fields of a tuple struct).
+    pub name: Option<Ident>,
+
+    /// The visibility of the field (empty if private).
+    pub visibility: TokenStream,
+
+    /// The attributes of the field.
+    pub attributes: Vec<Attribute>,
+
+    /// The type of the field.
+    pub ty: TokenStream,
+}
+
+impl Field {
+    /// Find the attribute of the field with the specified name.
+    pub fn attribute(self: &Field, name: &str) -> Option<&Attribute> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.name == name)
+    }
+}
+
+/// Represent a struct given to a derive macro.
+pub struct Struct {
+    /// The name of the struct.
+    pub name: Ident,
+
+    /// The attributes of the struct.
+    pub attributes: Vec<Attribute>,
+
+    /// The fields of the struct, in declaration order.
+    pub fields: Vec<Field>,
+}
+
+impl Struct {
+    /// Check if the struct has a #[repr(...)] attribute that contains one of the specified
+    /// representations.
+    pub fn has_repr(self: &Struct, representations: &[&str]) -> bool {
+        self.attributes
+            .iter()
+            .filter(|attribute| attribute.name == "repr")
+            .flat_map(|attribute| attribute.arguments.clone())
+            .any(|token| match token {
+                TokenTree::Ident(ident) => representations.contains(&ident.to_string().as_str()),
+                _ => false,
+            })
+    }
+}
+
+/// Parse the struct given to a derive macro (enums, unions and generic structs are rejected).
+///
+/// # Arguments
+/// input - The tokens of the item
+/// derive - The name of the derive macro (used in the error messages)
+///
+/// # Returns
+/// If the function succeeds, the return value is the parsed struct.
+pub fn parse_struct(input: TokenStream, derive: &str) -> Result<Struct, DeriveError> {
+    let mut tokens = input.into_iter().peekable();
+    let mut attributes = Vec::new();
+
+    while let Some(TokenTree::Punct(punct)) = tokens.peek() {
+        if punct.as_char() != '#' {
+            break;
+        }
+
+        tokens.next();
+        attributes.push(parse_attribute(tokens.next())?);
+    }
+
+    parse_visibility(&mut tokens);
+
+    let keyword = match tokens.next() {
+        Some(TokenTree::Ident(keyword)) => keyword,
+        _ => return Err(DeriveError::new(Span::call_site(), "expected a struct")),
+    };
+
+    if keyword.to_string() != "struct" {
+        return Err(DeriveError::new(
+            keyword.span(),
+            format!("{} can only be derived for structs", derive),
+        ));
+    }
+
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(name)) => name,
+        _ => return Err(DeriveError::new(keyword.span(), "expected a struct name")),
+    };
+
+    let fields = match tokens.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+            parse_fields(group.stream(), true)?
+        }
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+            parse_fields(group.stream(), false)?
+        }
+        Some(TokenTree::Punct(punct)) if punct.as_char() == ';' => Vec::new(),
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => {
+            return Err(DeriveError::new(
+                punct.span(),
+                format!("{} cannot be derived for generic structs", derive),
+            ))
+        }
+        Some(token) => {
+            return Err(DeriveError::new(
+                token.span(),
+                format!("{} cannot be derived for this struct", derive),
+            ))
+        }
+        None => return Err(DeriveError::new(name.span(), "expected the struct fields")),
+    };
+
+    Ok(Struct {
+        name,
+        attributes,
+        fields,
+    })
+}
+
+/// Parse the bracketed part of an attribute (the token after the #).
+fn parse_attribute(token: Option<TokenTree>) -> Result<Attribute, DeriveError> {
+    let group = match token {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Bracket => group,
+        _ => return Err(DeriveError::new(Span::call_site(), "expected an attribute")),
+    };
+
+    let mut tokens = group.stream().into_iter();
+
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(name)) => name.to_string(),
+        _ => String::new(),
+    };
+
+    let arguments = match tokens.next() {
+        Some(TokenTree::Group(arguments)) if arguments.delimiter() == Delimiter::Parenthesis => {
+            arguments.stream()
+        }
+        _ => TokenStream::new(),
+    };
+
+    Ok(Attribute {
+        name,
+        arguments,
+        span: group.span(),
+    })
+}
+
+/// Take the visibility (pub, pub(crate)...) at the current position, if any.
+fn parse_visibility(
+    tokens: &mut std::iter::Peekable<impl Iterator<Item = TokenTree>>,
+) -> TokenStream {
+    let mut visibility = Vec::new();
+
+    if let Some(TokenTree::Ident(ident)) = tokens.peek() {
+        if ident.to_string() == "pub" {
+            visibility.extend(tokens.next());
+
+            if let Some(TokenTree::Group(group)) = tokens.peek() {
+                if group.delimiter() == Delimiter::Parenthesis {
+                    visibility.extend(tokens.next());
+                }
+            }
+        }
+    }
+
+    visibility.into_iter().collect()
+}
+
+/// Parse the fields between the braces or the parentheses of a struct.
+///
+/// # Arguments
+/// stream - The tokens between the delimiters
+/// named - True if the fields have names (braces)
+///
+/// # Returns
+/// If the function succeeds, the return value is the list of fields.
+fn parse_fields(stream: TokenStream, named: bool) -> Result<Vec<Field>, DeriveError> {
+    let mut fields = Vec::new();
+
+    for tokens in split_commas(stream) {
+        let mut tokens = tokens.into_iter().peekable();
+        let mut attributes = Vec::new();
+
+        while let Some(TokenTree::Punct(punct)) = tokens.peek() {
+            if punct.as_char() != '#' {
+                break;
+            }
+
+            tokens.next();
+            attributes.push(parse_attribute(tokens.next())?);
+        }
+
+        let visibility = parse_visibility(&mut tokens);
+
+        let name = if named {
+            let name = match tokens.next() {
+                Some(TokenTree::Ident(name)) => name,
+                token => {
+                    return Err(DeriveError::new(
+                        token.map_or(Span::call_site(), |token| token.span()),
+                        "expected a field name",
+                    ))
+                }
+            };
+
+            match tokens.next() {
+                Some(TokenTree::Punct(colon)) if colon.as_char() == ':' => {}
+                _ => return Err(DeriveError::new(name.span(), "expected a field type")),
+            }
+
+            Some(name)
+        } else {
+            None
+        };
+
+        fields.push(Field {
+            name,
+            visibility,
+            attributes,
+            ty: tokens.collect(),
+        });
+    }
+
+    Ok(fields)
+}
+
+/// Split the tokens at the commas that are not part of a type (like the ones in `Map<K, V>`).
+fn split_commas(stream: TokenStream) -> Vec<Vec<TokenTree>> {
+    let mut parts = vec![Vec::new()];
+    let mut depth = 0usize;
+    let mut previous = None;
+
+    for token in stream {
+        if let TokenTree::Punct(punct) = &token {
+            match punct.as_char() {
+                '<' => depth += 1,
+                // The > of -> does not close a generic
+                '>' if previous != Some('-') => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => {
+                    parts.push(Vec::new());
+                    previous = Some(',');
+                    continue;
+                }
+                _ => {}
+            }
+
+            previous = Some(punct.as_char());
+        } else {
+            previous = None;
+        }
+
+        parts.last_mut().unwrap().push(token);
+    }
+
+    // A trailing comma leaves an empty part
+    parts.retain(|part| !part.is_empty());
+
+    parts
+}
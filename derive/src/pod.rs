@@ -0,0 +1,48 @@
+use proc_macro::TokenStream;
+
+use crate::parse::{self, DeriveError};
+
+/// Generate the Pod implementation of a struct.
+///
+/// # Arguments
+/// input - The tokens of the struct
+///
+/// # Returns
+/// If the function succeeds, the return value is the implementation and the compile time checks.
+pub fn expand(input: TokenStream) -> Result<TokenStream, DeriveError> {
+    let item = parse::parse_struct(input, "Pod")?;
+
+    if !item.has_repr(&["C", "transparent", "packed"]) {
+        return Err(DeriveError::new(
+            item.name.span(),
+            "Pod requires a defined layout, add #[repr(C)] to the struct",
+        ));
+    }
+
+    let name = item.name.to_string();
+    let mut checks = String::new();
+    let mut fields_size = String::from("0");
+
+    for field in &item.fields {
+        let ty = field.ty.to_string();
+
+        checks += &format!("const _: () = ::wapi::pod::assert_pod::<{}>();\n", ty);
+        fields_size += &format!(" + ::core::mem::size_of::<{}>()", ty);
+    }
+
+    let expanded = format!(
+        "const _: () = {{
+            {checks}
+            assert!(
+                ::core::mem::size_of::<{name}>() == {fields_size},
+                \"{name} has padding bytes, add explicit fields to fill them\"
+            );
+        }};
+
+        unsafe impl ::wapi::pod::Pod for {name} {{}}",
+    );
+
+    expanded
+        .parse()
+        .map_err(|_| DeriveError::new(item.name.span(), "cannot generate the Pod implementation"))
+}
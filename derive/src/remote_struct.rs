@@ -0,0 +1,147 @@
+use proc_macro::TokenStream;
+
+use crate::parse::{self, DeriveError, Field};
+
+/// The path of the generic process parameter of the generated functions.
+static PROCESS: &str = "process: &(impl ::wapi::backend::MemoryBackend + ?Sized)";
+
+/// The return type of the generated functions.
+static RESULT: &str = "::std::result::Result";
+
+/// Generate the RemoteStruct implementation and the field accessors of a struct.
+///
+/// # Arguments
+/// input - The tokens of the struct
+///
+/// # Returns
+/// If the function succeeds, the return value is the generated implementations.
+pub fn expand(input: TokenStream) -> Result<TokenStream, DeriveError> {
+    let item = parse::parse_struct(input, "RemoteStruct")?;
+
+    let name = item.name.to_string();
+    let mut initializers = String::new();
+    let mut accessors = String::new();
+
+    for field in &item.fields {
+        let field_name = match &field.name {
+            Some(field_name) => field_name,
+            None => {
+                return Err(DeriveError::new(
+                    item.name.span(),
+                    "RemoteStruct can only be derived for structs with named fields",
+                ))
+            }
+        };
+
+        let member = field_name.to_string();
+        let accessor = member.trim_start_matches("r#");
+
+        initializers += &format!("{member}: {name}::read_{accessor}(process, address)?,\n");
+        accessors += &expand_accessors(field, accessor)?;
+    }
+
+    let expanded = format!(
+        "impl ::wapi::remote_struct::RemoteStruct for {name} {{
+            fn read_from({PROCESS}, address: usize) -> {RESULT}<Self, ::std::io::Error> {{
+                Ok({name} {{ {initializers} }})
+            }}
+        }}
+
+        #[allow(dead_code)]
+        impl {name} {{ {accessors} }}",
+    );
+
+    expanded.parse().map_err(|_| {
+        DeriveError::new(
+            item.name.span(),
+            "cannot generate the RemoteStruct implementation",
+        )
+    })
+}
+
+/// Generate the functions that get the address of a field, read it and write it.
+///
+/// # Arguments
+/// field - The field
+/// accessor - The name of the field used in the names of the functions
+///
+/// # Returns
+/// If the function succeeds, the return value is the generated functions.
+fn expand_accessors(field: &Field, accessor: &str) -> Result<String, DeriveError> {
+    let field_span = field
+        .name
+        .as_ref()
+        .map(|name| name.span())
+        .unwrap_or_else(proc_macro::Span::call_site);
+
+    let offset = match field.attribute("offset") {
+        Some(offset) if !offset.arguments.is_empty() => offset.arguments.to_string(),
+        Some(offset) => {
+            return Err(DeriveError::new(
+                offset.span,
+                "expected the offset of the field, like #[offset(0x54)]",
+            ))
+        }
+        None => {
+            return Err(DeriveError::new(
+                field_span,
+                format!("missing #[offset(...)] on the field {}", accessor),
+            ))
+        }
+    };
+
+    let visibility = field.visibility.to_string();
+    let ty = field.ty.to_string();
+
+    let address = match field.attribute("pointer") {
+        Some(pointer) => format!(
+            "::wapi::remote_struct::follow(process, address.wrapping_add({offset}), &[{}])",
+            pointer.arguments
+        ),
+        None => format!("Ok(address.wrapping_add({offset}))"),
+    };
+
+    let mut accessors = format!(
+        "/// Get the address of the field {accessor} of the struct at the specified address.
+        {visibility} fn {accessor}_address({PROCESS}, address: usize)
+            -> {RESULT}<usize, ::std::io::Error> {{
+            {address}
+        }}\n"
+    );
+
+    if field.attribute("nested").is_some() {
+        accessors += &format!(
+            "/// Read the field {accessor} of the struct at the specified address.
+            {visibility} fn read_{accessor}({PROCESS}, address: usize)
+                -> {RESULT}<{ty}, ::std::io::Error> {{
+                <{ty} as ::wapi::remote_struct::RemoteStruct>::read_from(
+                    process,
+                    Self::{accessor}_address(process, address)?,
+                )
+            }}\n"
+        );
+    } else {
+        accessors += &format!(
+            "/// Read the field {accessor} of the struct at the specified address.
+            {visibility} fn read_{accessor}({PROCESS}, address: usize)
+                -> {RESULT}<{ty}, ::std::io::Error> {{
+                ::wapi::memory::read::<{ty}>(
+                    process,
+                    Self::{accessor}_address(process, address)? as *const ::core::ffi::c_void,
+                )
+            }}
+
+            /// Write the field {accessor} of the struct at the specified address.
+            {visibility} fn write_{accessor}({PROCESS}, address: usize, value: {ty})
+                -> {RESULT}<usize, ::std::io::Error> {{
+                ::wapi::memory::write::<{ty}>(
+                    process,
+                    Self::{accessor}_address(process, address)? as *const ::core::ffi::c_void,
+                    value,
+                )
+            }}\n"
+        );
+    }
+
+    Ok(accessors)
+}
@@ -0,0 +1,326 @@
+use std::fmt;
+use std::io::{Error, ErrorKind};
+use std::ops::BitOr;
+
+/// Represent the access rights of a memory region.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Protection(u8);
+
+impl Protection {
+    /// The region cannot be accessed.
+    pub const NONE: Protection = Protection(0b000);
+
+    /// The region can be read.
+    pub const READ: Protection = Protection(0b001);
+
+    /// The region can be written.
+    pub const WRITE: Protection = Protection(0b010);
+
+    /// The region can be executed.
+    pub const EXECUTE: Protection = Protection(0b100);
+
+    /// Check if the protection grants all the access rights of the specified protection.
+    ///
+    /// # Arguments
+    /// self - The protection to check
+    /// other - The access rights that must be granted
+    ///
+    /// # Returns
+    /// True if all the access rights of other are granted
+    pub fn contains(self: Protection, other: Protection) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Check if the protection allows to read the region.
+    pub fn is_readable(self: Protection) -> bool {
+        self.contains(Protection::READ)
+    }
+
+    /// Check if the protection allows to write the region.
+    pub fn is_writable(self: Protection) -> bool {
+        self.contains(Protection::WRITE)
+    }
+
+    /// Check if the protection allows to execute the region.
+    pub fn is_executable(self: Protection) -> bool {
+        self.contains(Protection::EXECUTE)
+    }
+}
+
+impl BitOr for Protection {
+    type Output = Protection;
+
+    fn bitor(self, rhs: Protection) -> Protection {
+        Protection(self.0 | rhs.0)
+    }
+}
+
+impl fmt::Display for Protection {
+    /// Format the protection as the well known "rwx" notation.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let r = if self.is_readable() { 'r' } else { '-' };
+        let w = if self.is_writable() { 'w' } else { '-' };
+        let x = if self.is_executable() { 'x' } else { '-' };
+
+        write!(f, "{}{}{}", r, w, x)
+    }
+}
+
+/// Represent the allocation state of a memory region.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RegionState {
+    /// The region is backed by physical memory or a file and can be accessed.
+    Committed,
+
+    /// The region is reserved in the address space but not backed by memory.
+    Reserved,
+
+    /// The region is not part of the address space of the process.
+    Free,
+}
+
+/// Represent the kind of pages that compose a memory region.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RegionType {
+    /// The pages are mapped from an executable image (executable, library, module...).
+    Image,
+
+    /// The pages are mapped from a file or a shared memory section.
+    Mapped,
+
+    /// The pages are private to the process (heap, stack...).
+    Private,
+
+    /// The kind of pages is not known (free regions for example).
+    Unknown,
+}
+
+/// Represent a contiguous range of pages sharing the same attributes in a process.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemoryRegion {
+    /// The address of the first byte of the region.
+    pub base_address: usize,
+
+    /// The size of the region in bytes.
+    pub size: usize,
+
+    /// The access rights of the region.
+    pub protection: Protection,
+
+    /// The allocation state of the region.
+    pub state: RegionState,
+
+    /// The kind of pages that compose the region.
+    pub kind: RegionType,
+
+    /// The file that backs the region (mapped files and images) or its pseudo-path on Linux
+    /// ([heap], [stack]...) if any.
+    pub path: Option<String>,
+}
+
+impl MemoryRegion {
+    /// Get the address following the last byte of the region.
+    pub fn end_address(self: &MemoryRegion) -> usize {
+        self.base_address.saturating_add(self.size)
+    }
+
+    /// Check if the specified address is inside the region.
+    ///
+    /// # Arguments
+    /// self - The region to check
+    /// address - The address to search
+    ///
+    /// # Returns
+    /// True if the address is inside the region
+    pub fn contains(self: &MemoryRegion, address: usize) -> bool {
+        address >= self.base_address && address < self.end_address()
+    }
+
+    /// Check if the region is committed and can be read.
+    pub fn is_readable(self: &MemoryRegion) -> bool {
+        self.state == RegionState::Committed && self.protection.is_readable()
+    }
+}
+
+/// Represent an executable image (executable, library...) loaded in an address space.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Module {
+    /// The file name of the module (ex: "engine.dll").
+    pub name: String,
+
+    /// The full path of the module file.
+    pub path: String,
+
+    /// The address where the module is loaded.
+    pub base_address: usize,
+
+    /// The size of the loaded module in bytes.
+    pub size: usize,
+
+    /// The address of the entry point of the module (0 if the module has no entry point).
+    pub entry_point: usize,
+}
+
+impl Module {
+    /// Get the address following the last byte of the module.
+    pub fn end_address(self: &Module) -> usize {
+        self.base_address.saturating_add(self.size)
+    }
+
+    /// Check if the specified address is inside the module.
+    ///
+    /// # Arguments
+    /// self - The module to check
+    /// address - The address to search
+    ///
+    /// # Returns
+    /// True if the address is inside the module
+    pub fn contains(self: &Module, address: usize) -> bool {
+        address >= self.base_address && address < self.end_address()
+    }
+}
+
+/// Represent something that owns an address space that can be read and written (a live process,
+/// a fake process for tests, a memory dump...).
+///
+/// Every generic helper of the `memory` module is written against this trait.
+pub trait MemoryBackend {
+    /// Read bytes at the specified address of the address space.
+    ///
+    /// # Arguments
+    /// address - The address to read from.
+    /// buffer - The buffer to read into (its length is the number of bytes to read).
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the number of bytes read.
+    fn read_bytes(&self, address: usize, buffer: &mut [u8]) -> Result<usize, Error>;
+
+    /// Read several ranges of the address space at once (a failed range does not stop the others).
+    ///
+    /// The default implementation reads the ranges one by one with read_bytes.
+    ///
+    /// # Arguments
+    /// requests - The addresses to read from, with the buffers to read into.
+    ///
+    /// # Returns
+    /// The result of every request, in the same order.
+    fn read_many(&self, requests: &mut [(usize, &mut [u8])]) -> Vec<Result<usize, Error>> {
+        requests
+            .iter_mut()
+            .map(|(address, buffer)| self.read_bytes(*address, buffer))
+            .collect()
+    }
+
+    /// Write bytes at the specified address of the address space.
+    ///
+    /// # Arguments
+    /// address - The address to write to.
+    /// buffer - The bytes to write.
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the number of bytes written.
+    fn write_bytes(&self, address: usize, buffer: &[u8]) -> Result<usize, Error>;
+
+    /// Allocate a new region in the address space.
+    ///
+    /// # Arguments
+    /// size - The size of the memory to allocate.
+    /// protection - The access rights of the allocated memory.
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the base address of the allocated memory.
+    fn allocate(&self, size: usize, protection: Protection) -> Result<usize, Error>;
+
+    /// Retrieve the region that contains the specified address.
+    ///
+    /// # Arguments
+    /// address - The address to query.
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the region (free if the address is not mapped).
+    fn query_region(&self, address: usize) -> Result<MemoryRegion, Error>;
+
+    /// Retrieve every region that is part of the address space (free regions are skipped).
+    ///
+    /// The default implementation walks the address space with query_region, from the address 0
+    /// to the first address that cannot be queried (the end of the user address space).
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the list of regions sorted by address.
+    fn regions(&self) -> Result<Vec<MemoryRegion>, Error> {
+        let mut regions = Vec::new();
+        let mut address = 0;
+
+        loop {
+            let region = match self.query_region(address) {
+                Ok(region) => region,
+                Err(error) if address == 0 => return Err(error),
+                Err(_) => break,
+            };
+
+            let next_address = region.end_address();
+
+            if region.state != RegionState::Free {
+                regions.push(region);
+            }
+
+            if next_address <= address {
+                break;
+            }
+
+            address = next_address;
+        }
+
+        Ok(regions)
+    }
+
+    /// Get the base address of the main module (the executable) of the address space.
+    fn main_module_base(&self) -> usize;
+
+    /// Retrieve the modules loaded in the address space.
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the list of loaded modules.
+    fn modules(&self) -> Result<Vec<Module>, Error> {
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "this memory backend cannot enumerate its modules",
+        ))
+    }
+}
+
+/// A reference to a memory backend is a memory backend, so wrappers (like the cache) can either
+/// own or borrow the backend they wrap.
+impl<T: MemoryBackend + ?Sized> MemoryBackend for &T {
+    fn read_bytes(&self, address: usize, buffer: &mut [u8]) -> Result<usize, Error> {
+        (**self).read_bytes(address, buffer)
+    }
+
+    fn read_many(&self, requests: &mut [(usize, &mut [u8])]) -> Vec<Result<usize, Error>> {
+        (**self).read_many(requests)
+    }
+
+    fn write_bytes(&self, address: usize, buffer: &[u8]) -> Result<usize, Error> {
+        (**self).write_bytes(address, buffer)
+    }
+
+    fn allocate(&self, size: usize, protection: Protection) -> Result<usize, Error> {
+        (**self).allocate(size, protection)
+    }
+
+    fn query_region(&self, address: usize) -> Result<MemoryRegion, Error> {
+        (**self).query_region(address)
+    }
+
+    fn regions(&self) -> Result<Vec<MemoryRegion>, Error> {
+        (**self).regions()
+    }
+
+    fn main_module_base(&self) -> usize {
+        (**self).main_module_base()
+    }
+
+    fn modules(&self) -> Result<Vec<Module>, Error> {
+        (**self).modules()
+    }
+}
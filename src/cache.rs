@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+use std::io::Error;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::backend::{MemoryBackend, MemoryRegion, Module, Protection};
+
+/// The size of the pages kept by the cache.
+pub const CACHE_PAGE_SIZE: usize = 0x1000;
+
+/// Represent a page copied from the wrapped process.
+struct CachedPage {
+    /// The content of the page.
+    data: Box<[u8; CACHE_PAGE_SIZE]>,
+
+    /// When the page was read from the process.
+    read_at: Instant,
+}
+
+/// Represent the pages kept by the cache.
+struct CachedPages {
+    /// The pages, indexed by their base address.
+    entries: HashMap<usize, CachedPage>,
+
+    /// When the expired pages were last removed.
+    swept_at: Instant,
+}
+
+/// Represent a process whose pages are kept in memory after being read, so reading the same
+/// pages again (like walking pointer chains that share a prefix) does not read the process again.
+///
+/// The cached pages expire after the time-to-live (if any) or when they are invalidated. The
+/// reads remove the expired pages (at most once per time-to-live), so a long-running cache only
+/// keeps the pages read recently. The writes go through to the process and update the cached
+/// copies of the written pages. A read that touches a page that cannot be read as a whole is
+/// forwarded to the process uncached.
+pub struct CachedProcess<P: MemoryBackend> {
+    /// The wrapped process.
+    pub process: P,
+
+    /// How long a page is used before being read again (None to keep it until invalidated).
+    pub time_to_live: Option<Duration>,
+
+    /// The cached pages.
+    pages: Mutex<CachedPages>,
+}
+
+impl<P: MemoryBackend> CachedProcess<P> {
+    /// Wrap the specified process in a cache.
+    ///
+    /// # Arguments
+    /// process - The process to read from (a reference to a process can be used)
+    /// time_to_live - How long a page is used before being read again (None to keep it until
+    ///                invalidated)
+    ///
+    /// # Returns
+    /// The process with an empty cache
+    pub fn new(process: P, time_to_live: Option<Duration>) -> CachedProcess<P> {
+        CachedProcess {
+            process,
+            time_to_live,
+            pages: Mutex::new(CachedPages {
+                entries: HashMap::new(),
+                swept_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Drop the cached pages that cover the specified range, so they are read again.
+    ///
+    /// # Arguments
+    /// self - The cached process
+    /// address - The first address of the range
+    /// size - The size of the range
+    pub fn invalidate(self: &CachedProcess<P>, address: usize, size: usize) {
+        let mut pages = self.pages.lock().unwrap();
+
+        for page_address in page_range(address, size) {
+            pages.entries.remove(&page_address);
+        }
+    }
+
+    /// Drop every cached page.
+    pub fn invalidate_all(self: &CachedProcess<P>) {
+        self.pages.lock().unwrap().entries.clear();
+    }
+
+    /// Get the number of pages in the cache (the expired pages not removed by a read yet
+    /// included).
+    pub fn cached_pages(self: &CachedProcess<P>) -> usize {
+        self.pages.lock().unwrap().entries.len()
+    }
+
+    /// Check if the specified cached page can still be used.
+    fn is_fresh(self: &CachedProcess<P>, page: &CachedPage) -> bool {
+        self.time_to_live
+            .is_none_or(|time_to_live| page.read_at.elapsed() < time_to_live)
+    }
+}
+
+impl<P: MemoryBackend> MemoryBackend for CachedProcess<P> {
+    fn read_bytes(&self, address: usize, buffer: &mut [u8]) -> Result<usize, Error> {
+        if buffer.is_empty() || address.checked_add(buffer.len()).is_none() {
+            return self.process.read_bytes(address, buffer);
+        }
+
+        let mut pages = self.pages.lock().unwrap();
+
+        // The pages that are not read anymore would stay forever otherwise
+        if self
+            .time_to_live
+            .is_some_and(|time_to_live| pages.swept_at.elapsed() >= time_to_live)
+        {
+            pages.entries.retain(|_, page| self.is_fresh(page));
+            pages.swept_at = Instant::now();
+        }
+
+        // Fetch the missing or expired pages before copying anything
+        for page_address in page_range(address, buffer.len()) {
+            if pages
+                .entries
+                .get(&page_address)
+                .is_some_and(|page| self.is_fresh(page))
+            {
+                continue;
+            }
+
+            let mut data = Box::new([0u8; CACHE_PAGE_SIZE]);
+
+            if self
+                .process
+                .read_bytes(page_address, data.as_mut_slice())
+                .is_err()
+            {
+                pages.entries.remove(&page_address);
+                drop(pages);
+
+                return self.process.read_bytes(address, buffer);
+            }
+
+            pages.entries.insert(
+                page_address,
+                CachedPage {
+                    data,
+                    read_at: Instant::now(),
+                },
+            );
+        }
+
+        let mut done = 0;
+
+        while done < buffer.len() {
+            let current = address + done;
+            let page_offset = current % CACHE_PAGE_SIZE;
+            let length = (CACHE_PAGE_SIZE - page_offset).min(buffer.len() - done);
+
+            let page = &pages.entries[&(current - page_offset)];
+
+            buffer[done..done + length]
+                .copy_from_slice(&page.data[page_offset..page_offset + length]);
+
+            done += length;
+        }
+
+        Ok(buffer.len())
+    }
+
+    fn write_bytes(&self, address: usize, buffer: &[u8]) -> Result<usize, Error> {
+        let result = self.process.write_bytes(address, buffer);
+
+        let written = match &result {
+            Ok(written) => *written,
+            Err(_) => {
+                // The write may be partial, the process has to be read again
+                self.invalidate(address, buffer.len());
+                return result;
+            }
+        };
+
+        let mut pages = self.pages.lock().unwrap();
+        let mut done = 0;
+
+        while done < written {
+            let current = address + done;
+            let page_offset = current % CACHE_PAGE_SIZE;
+            let length = (CACHE_PAGE_SIZE - page_offset).min(written - done);
+
+            if let Some(page) = pages.entries.get_mut(&(current - page_offset)) {
+                page.data[page_offset..page_offset + length]
+                    .copy_from_slice(&buffer[done..done + length]);
+            }
+
+            done += length;
+        }
+
+        result
+    }
+
+    fn allocate(&self, size: usize, protection: Protection) -> Result<usize, Error> {
+        self.process.allocate(size, protection)
+    }
+
+    fn query_region(&self, address: usize) -> Result<MemoryRegion, Error> {
+        self.process.query_region(address)
+    }
+
+    fn regions(&self) -> Result<Vec<MemoryRegion>, Error> {
+        self.process.regions()
+    }
+
+    fn main_module_base(&self) -> usize {
+        self.process.main_module_base()
+    }
+
+    fn modules(&self) -> Result<Vec<Module>, Error> {
+        self.process.modules()
+    }
+}
+
+/// Get the base address of every cache page that covers the specified range.
+///
+/// # Arguments
+/// address - The first address of the range
+/// size - The size of the range
+///
+/// # Returns
+/// An iterator over the base address of the pages.
+fn page_range(address: usize, size: usize) -> impl Iterator<Item = usize> {
+    let first = address - address % CACHE_PAGE_SIZE;
+    let last = address.saturating_add(size.max(1) - 1);
+
+    (first..=last).step_by(CACHE_PAGE_SIZE)
+}
@@ -0,0 +1,356 @@
+use std::ffi::c_void;
+use std::io::{Error, ErrorKind};
+use std::mem::{align_of, size_of};
+
+use crate::backend::MemoryBackend;
+use crate::memory;
+use crate::pod::{self, Pod};
+
+/// Read the specified number of elements stored one after the other.
+///
+/// # Arguments
+/// process - The process to read from.
+/// address - The address of the first element.
+/// count - The number of elements.
+/// max_count - The maximum number of elements to accept.
+///
+/// # Returns
+/// If the function succeeds, the return value is an iterator over the elements. It fails with
+/// InvalidData if count is more than max_count.
+pub fn read_array<T: Pod>(
+    process: &(impl MemoryBackend + ?Sized),
+    address: usize,
+    count: usize,
+    max_count: usize,
+) -> Result<impl Iterator<Item = T>, Error> {
+    check_count(count, max_count)?;
+
+    let size = element_size::<T>()?;
+
+    let total_size = count.checked_mul(size).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("{} elements of {} bytes overflow", count, size),
+        )
+    })?;
+
+    let mut bytes = vec![0u8; total_size];
+
+    process.read_bytes(address, &mut bytes)?;
+
+    Ok(bytes
+        .chunks_exact(size)
+        .map(|element| pod::from_bytes::<T>(element).unwrap())
+        .collect::<Vec<T>>()
+        .into_iter())
+}
+
+/// Read the elements of a std::vector (MSVC, libstdc++ and libc++ release builds), made of the
+/// pointers to the first element, after the last element and after the allocated storage.
+///
+/// # Arguments
+/// process - The process to read from.
+/// address - The address of the std::vector object.
+/// max_count - The maximum number of elements to accept.
+///
+/// # Returns
+/// If the function succeeds, the return value is an iterator over the elements. It fails with
+/// InvalidData if the pointers are not consistent or if there are more than max_count elements.
+pub fn read_std_vector<T: Pod>(
+    process: &(impl MemoryBackend + ?Sized),
+    address: usize,
+    max_count: usize,
+) -> Result<impl Iterator<Item = T>, Error> {
+    let first = read_pointer(process, address)?;
+    let last = read_pointer(process, address.wrapping_add(size_of::<usize>()))?;
+    let end = read_pointer(process, address.wrapping_add(2 * size_of::<usize>()))?;
+
+    let size = element_size::<T>()?;
+
+    if first > last || last > end || (last - first) % size != 0 {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "invalid std::vector at {:#x} (first {:#x}, last {:#x}, end {:#x})",
+                address, first, last, end
+            ),
+        ));
+    }
+
+    read_array(process, first, (last - first) / size, max_count)
+}
+
+/// Read the elements of an Unreal Engine TArray, made of the pointer to the elements, then the
+/// number of elements and the allocated number of elements (both i32).
+///
+/// # Arguments
+/// process - The process to read from.
+/// address - The address of the TArray object.
+/// max_count - The maximum number of elements to accept.
+///
+/// # Returns
+/// If the function succeeds, the return value is an iterator over the elements. It fails with
+/// InvalidData if the counts are not consistent or if there are more than max_count elements.
+pub fn read_tarray<T: Pod>(
+    process: &(impl MemoryBackend + ?Sized),
+    address: usize,
+    max_count: usize,
+) -> Result<impl Iterator<Item = T>, Error> {
+    let data = read_pointer(process, address)?;
+    let count = memory::read::<i32>(
+        process,
+        address.wrapping_add(size_of::<usize>()) as *const c_void,
+    )?;
+    let capacity = memory::read::<i32>(
+        process,
+        address.wrapping_add(size_of::<usize>() + 4) as *const c_void,
+    )?;
+
+    if count < 0 || count > capacity {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "invalid TArray at {:#x} (count {}, capacity {})",
+                address, count, capacity
+            ),
+        ));
+    }
+
+    read_array(process, data, count as usize, max_count)
+}
+
+/// Read the elements of a Unity IL2CPP array (Il2CppArray), made of the class, the monitor and
+/// the bounds pointers, then the number of elements and the elements.
+///
+/// # Arguments
+/// process - The process to read from.
+/// address - The address of the array object.
+/// max_count - The maximum number of elements to accept.
+///
+/// # Returns
+/// If the function succeeds, the return value is an iterator over the elements. It fails with
+/// InvalidData if there are more than max_count elements.
+pub fn read_il2cpp_array<T: Pod>(
+    process: &(impl MemoryBackend + ?Sized),
+    address: usize,
+    max_count: usize,
+) -> Result<impl Iterator<Item = T>, Error> {
+    let count = read_pointer(process, address.wrapping_add(3 * size_of::<usize>()))?;
+    let elements = align_up(4 * size_of::<usize>(), align_of::<T>());
+
+    read_array(process, address.wrapping_add(elements), count, max_count)
+}
+
+/// Read the elements of a linked list (singly or doubly linked), from the first node to a null
+/// pointer or back to the first node (circular lists).
+///
+/// # Arguments
+/// process - The process to read from.
+/// first - The address of the first node (null for an empty list).
+/// next_offset - The offset of the pointer to the next node in a node.
+/// value_offset - The offset of the element in a node.
+/// max_count - The maximum number of elements to accept.
+///
+/// # Returns
+/// If the function succeeds, the return value is an iterator over the elements. It fails with
+/// InvalidData if there are more than max_count elements (a list corrupted into a loop).
+pub fn read_linked_list<T: Pod>(
+    process: &(impl MemoryBackend + ?Sized),
+    first: usize,
+    next_offset: usize,
+    value_offset: usize,
+    max_count: usize,
+) -> Result<impl Iterator<Item = T>, Error> {
+    let mut elements = Vec::new();
+    let mut node = first;
+
+    while node != 0 {
+        check_count(elements.len() + 1, max_count)?;
+
+        elements.push(read_value(process, node.wrapping_add(value_offset))?);
+
+        node = read_pointer(process, node.wrapping_add(next_offset))?;
+
+        if node == first {
+            break;
+        }
+    }
+
+    Ok(elements.into_iter())
+}
+
+/// Read the elements of a MSVC std::list, made of the pointer to the sentinel node and the
+/// number of elements (a node is the next and previous pointers, then the element).
+///
+/// # Arguments
+/// process - The process to read from.
+/// address - The address of the std::list object.
+/// max_count - The maximum number of elements to accept.
+///
+/// # Returns
+/// If the function succeeds, the return value is an iterator over the elements. It fails with
+/// InvalidData if the nodes do not match the size or if there are more than max_count elements.
+pub fn read_msvc_list<T: Pod>(
+    process: &(impl MemoryBackend + ?Sized),
+    address: usize,
+    max_count: usize,
+) -> Result<impl Iterator<Item = T>, Error> {
+    let sentinel = read_pointer(process, address)?;
+    let count = read_pointer(process, address.wrapping_add(size_of::<usize>()))?;
+
+    read_sentinel_list(process, sentinel, count, max_count)
+}
+
+/// Read the elements of a libstdc++ std::list, made of the sentinel node (next and previous
+/// pointers) and the number of elements (a node is the next and previous pointers, then the
+/// element).
+///
+/// # Arguments
+/// process - The process to read from.
+/// address - The address of the std::list object.
+/// max_count - The maximum number of elements to accept.
+///
+/// # Returns
+/// If the function succeeds, the return value is an iterator over the elements. It fails with
+/// InvalidData if the nodes do not match the size or if there are more than max_count elements.
+pub fn read_libstdcpp_list<T: Pod>(
+    process: &(impl MemoryBackend + ?Sized),
+    address: usize,
+    max_count: usize,
+) -> Result<impl Iterator<Item = T>, Error> {
+    let count = read_pointer(process, address.wrapping_add(2 * size_of::<usize>()))?;
+
+    read_sentinel_list(process, address, count, max_count)
+}
+
+/// Read the elements of a MSVC std::unordered_map. The buckets point into a std::list that links
+/// every element, so the (key, value) pairs are read from that list.
+///
+/// # Arguments
+/// process - The process to read from.
+/// address - The address of the std::unordered_map object.
+/// max_count - The maximum number of elements to accept.
+///
+/// # Returns
+/// If the function succeeds, the return value is an iterator over the (key, value) pairs, in the
+/// order of the list. It fails with InvalidData if the list is not consistent or if there are
+/// more than max_count elements.
+pub fn read_msvc_unordered_map<K: Pod, V: Pod>(
+    process: &(impl MemoryBackend + ?Sized),
+    address: usize,
+    max_count: usize,
+) -> Result<impl Iterator<Item = (K, V)>, Error> {
+    // The list follows the maximum load factor (a float padded to a pointer)
+    let list = address.wrapping_add(size_of::<usize>());
+    let sentinel = read_pointer(process, list)?;
+    let count = read_pointer(process, list.wrapping_add(size_of::<usize>()))?;
+
+    check_count(count, max_count)?;
+
+    let pair_offset = align_up(2 * size_of::<usize>(), align_of::<K>().max(align_of::<V>()));
+    let value_offset = align_up(size_of::<K>(), align_of::<V>());
+
+    let nodes = sentinel_nodes(process, sentinel, count)?;
+    let mut pairs = Vec::with_capacity(count);
+
+    for node in nodes {
+        let pair = node.wrapping_add(pair_offset);
+
+        pairs.push((
+            read_value::<K>(process, pair)?,
+            read_value::<V>(process, pair.wrapping_add(value_offset))?,
+        ));
+    }
+
+    Ok(pairs.into_iter())
+}
+
+/// Read the elements of a circular list that starts and ends at a sentinel node.
+fn read_sentinel_list<T: Pod>(
+    process: &(impl MemoryBackend + ?Sized),
+    sentinel: usize,
+    count: usize,
+    max_count: usize,
+) -> Result<std::vec::IntoIter<T>, Error> {
+    check_count(count, max_count)?;
+
+    let value_offset = align_up(2 * size_of::<usize>(), align_of::<T>());
+
+    let elements = sentinel_nodes(process, sentinel, count)?
+        .into_iter()
+        .map(|node| read_value(process, node.wrapping_add(value_offset)))
+        .collect::<Result<Vec<T>, Error>>()?;
+
+    Ok(elements.into_iter())
+}
+
+/// Get the addresses of the nodes of a circular list that starts and ends at a sentinel node.
+///
+/// # Arguments
+/// process - The process to read from.
+/// sentinel - The address of the sentinel node (the next pointer is its first field).
+/// count - The number of nodes after the sentinel.
+///
+/// # Returns
+/// If the function succeeds, the return value is the address of every node. It fails with
+/// InvalidData if the list does not go back to the sentinel after count nodes.
+fn sentinel_nodes(
+    process: &(impl MemoryBackend + ?Sized),
+    sentinel: usize,
+    count: usize,
+) -> Result<Vec<usize>, Error> {
+    let mut nodes = Vec::with_capacity(count);
+    let mut node = read_pointer(process, sentinel)?;
+
+    while node != sentinel && nodes.len() < count {
+        nodes.push(node);
+        node = read_pointer(process, node)?;
+    }
+
+    if node != sentinel || nodes.len() != count {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("the list at {:#x} does not have {} nodes", sentinel, count),
+        ));
+    }
+
+    Ok(nodes)
+}
+
+/// Fail if the number of elements is more than the maximum.
+fn check_count(count: usize, max_count: usize) -> Result<(), Error> {
+    if count > max_count {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("{} elements exceed the maximum of {}", count, max_count),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Get the size of the elements, failing for zero-sized types.
+fn element_size<T>() -> Result<usize, Error> {
+    match size_of::<T>() {
+        0 => Err(Error::new(
+            ErrorKind::InvalidInput,
+            "the elements cannot be zero-sized",
+        )),
+        size => Ok(size),
+    }
+}
+
+/// Read the pointer at the specified address.
+fn read_pointer(process: &(impl MemoryBackend + ?Sized), address: usize) -> Result<usize, Error> {
+    memory::read::<usize>(process, address as *const c_void)
+}
+
+/// Read the value at the specified address.
+fn read_value<T: Pod>(process: &(impl MemoryBackend + ?Sized), address: usize) -> Result<T, Error> {
+    memory::read::<T>(process, address as *const c_void)
+}
+
+/// Round the offset up to the specified alignment.
+fn align_up(offset: usize, align: usize) -> usize {
+    offset.div_ceil(align) * align
+}
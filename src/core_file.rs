@@ -0,0 +1,405 @@
+use std::fs::File;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+use crate::backend::{MemoryBackend, MemoryRegion, Module, Protection, RegionState, RegionType};
+use crate::elf::{self, ELF_CLASS_64, ELF_MAGIC};
+use crate::file_format::{self, read_at, u16_at, u32_at, u64_at, FileBlock, FileMemory};
+
+/// e_type of the core files.
+const ET_CORE: u16 = 4;
+
+/// The types of the program headers.
+const PT_LOAD: u32 = 1;
+const PT_NOTE: u32 = 4;
+
+/// The flags of the program headers.
+const PF_X: u32 = 1;
+const PF_W: u32 = 2;
+const PF_R: u32 = 4;
+
+/// The types of the notes (named "CORE").
+const NT_PRSTATUS: u32 = 1;
+const NT_PRPSINFO: u32 = 3;
+const NT_AUXV: u32 = 6;
+const NT_FILE: u32 = 0x4649_4C45;
+
+/// The auxiliary vector entry that contains the entry point of the executable.
+const AT_ENTRY: u64 = 9;
+
+/// Represent a file mapped in the address space of the crashed process (NT_FILE note).
+struct FileMapping {
+    /// The first address of the mapping.
+    start: usize,
+
+    /// The address after the mapping.
+    end: usize,
+
+    /// The path of the mapped file.
+    path: String,
+}
+
+/// Represent a Linux core file loaded from the disk, usable as a read-only process.
+///
+/// The bytes of the PT_LOAD segments stay in the file and are read on demand. The parts of the
+/// segments that the kernel did not dump (like the code of the mapped files) cannot be read.
+pub struct CoreFile {
+    /// The process identifier of the crashed process.
+    pub pid: u32,
+
+    /// The name of the executable (truncated to 15 characters by the kernel).
+    pub name: String,
+
+    /// The identifiers of the threads, the thread that crashed first.
+    pub threads: Vec<u32>,
+
+    /// The modules derived from the file mappings.
+    pub modules: Vec<Module>,
+
+    /// The base address of the main module.
+    main_module_base: usize,
+
+    /// The regions of the process (sorted by address).
+    regions: Vec<MemoryRegion>,
+
+    /// The dumped bytes.
+    memory: FileMemory,
+}
+
+impl CoreFile {
+    /// Open a core file (64 or 32 bits little-endian ELF).
+    ///
+    /// # Arguments
+    /// file - The path of the core file
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the core file, ready to be read.
+    pub fn load(file: impl AsRef<Path>) -> Result<CoreFile, Error> {
+        let mut file = File::open(file)?;
+
+        let header = read_at(&mut file, 0, 64).or_else(|_| read_at(&mut file, 0, 52))?;
+
+        if &header[0..4] != ELF_MAGIC || u16_at(&header, 16)? != ET_CORE {
+            return Err(Error::new(ErrorKind::InvalidData, "not an ELF core file"));
+        }
+
+        if header[5] != 1 {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "big-endian core files are not supported",
+            ));
+        }
+
+        let is_64 = header[4] == ELF_CLASS_64;
+
+        let (ph_offset, ph_size, ph_count) = if is_64 {
+            (
+                u64_at(&header, 32)?,
+                u16_at(&header, 54)?,
+                u16_at(&header, 56)?,
+            )
+        } else {
+            (
+                u32_at(&header, 28)? as u64,
+                u16_at(&header, 42)?,
+                u16_at(&header, 44)?,
+            )
+        };
+
+        let program_headers = read_at(&mut file, ph_offset, ph_size as usize * ph_count as usize)?;
+
+        // (address, size, flags) of the PT_LOAD segments
+        let mut segments = Vec::new();
+        let mut blocks = Vec::new();
+        let mut notes = Vec::new();
+
+        for i in 0..ph_count as usize {
+            let ph = &program_headers[i * ph_size as usize..];
+
+            let (p_type, flags, offset, address, file_size, memory_size) = if is_64 {
+                (
+                    u32_at(ph, 0)?,
+                    u32_at(ph, 4)?,
+                    u64_at(ph, 8)?,
+                    u64_at(ph, 16)?,
+                    u64_at(ph, 32)?,
+                    u64_at(ph, 40)?,
+                )
+            } else {
+                (
+                    u32_at(ph, 0)?,
+                    u32_at(ph, 24)?,
+                    u32_at(ph, 4)? as u64,
+                    u32_at(ph, 8)? as u64,
+                    u32_at(ph, 16)? as u64,
+                    u32_at(ph, 20)? as u64,
+                )
+            };
+
+            match p_type {
+                PT_LOAD => {
+                    if address.checked_add(memory_size).is_none() {
+                        return Err(Error::new(
+                            ErrorKind::InvalidData,
+                            format!("segment {:#x}+{:#x} overflows", address, memory_size),
+                        ));
+                    }
+
+                    segments.push((address as usize, memory_size as usize, flags));
+
+                    if file_size > 0 {
+                        blocks.push(FileBlock {
+                            address: address as usize,
+                            size: file_size.min(memory_size) as usize,
+                            file_offset: offset,
+                        });
+                    }
+                }
+                PT_NOTE => notes.push(read_at(&mut file, offset, file_size as usize)?),
+                _ => {}
+            }
+        }
+
+        let mut pid = 0;
+        let mut name = String::new();
+        let mut threads = Vec::new();
+        let mut entry_point = None;
+        let mut mappings = Vec::new();
+
+        let word_size = if is_64 { 8 } else { 4 };
+        let word_at = |bytes: &[u8], offset: usize| -> Result<u64, Error> {
+            if is_64 {
+                u64_at(bytes, offset)
+            } else {
+                Ok(u32_at(bytes, offset)? as u64)
+            }
+        };
+
+        for (note_type, desc) in notes.iter().flat_map(|notes| parse_notes(notes)) {
+            match note_type {
+                // elf_prstatus.pr_pid is after pr_info, pr_cursig, pr_sigpend and pr_sighold
+                NT_PRSTATUS => threads.push(u32_at(desc, 12 + 4 + 2 * word_size)?),
+                // elf_prpsinfo.pr_pid / pr_fname
+                NT_PRPSINFO => {
+                    let (pid_offset, name_offset) = if is_64 { (24, 40) } else { (12, 28) };
+                    let fname = desc.get(name_offset..name_offset + 16).unwrap_or(&[]);
+
+                    pid = u32_at(desc, pid_offset)?;
+                    name = String::from_utf8_lossy(fname)
+                        .trim_end_matches('\0')
+                        .to_string();
+                }
+                NT_AUXV => {
+                    for entry in (0..desc.len() / (2 * word_size)).map(|i| i * 2 * word_size) {
+                        if word_at(desc, entry)? == AT_ENTRY {
+                            entry_point = Some(word_at(desc, entry + word_size)? as usize);
+                        }
+                    }
+                }
+                // count, page size, count * (start, end, page offset), then the paths
+                NT_FILE => {
+                    let count = word_at(desc, 0)? as usize;
+                    let paths_offset = count
+                        .checked_mul(3 * word_size)
+                        .and_then(|entries_size| entries_size.checked_add(2 * word_size));
+
+                    let paths_offset = match paths_offset {
+                        Some(paths_offset) if paths_offset <= desc.len() => paths_offset,
+                        _ => {
+                            return Err(Error::new(
+                                ErrorKind::InvalidData,
+                                format!("NT_FILE note with {} files is truncated", count),
+                            ))
+                        }
+                    };
+                    let paths = desc.get(paths_offset..).unwrap_or(&[]).split(|c| *c == 0);
+
+                    for (i, path) in paths.take(count).enumerate() {
+                        let entry = 2 * word_size + i * 3 * word_size;
+
+                        mappings.push(FileMapping {
+                            start: word_at(desc, entry)? as usize,
+                            end: word_at(desc, entry + word_size)? as usize,
+                            path: String::from_utf8_lossy(path).to_string(),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let path_of = |address: usize| {
+            mappings
+                .iter()
+                .find(|mapping| mapping.start <= address && address < mapping.end)
+                .map(|mapping| mapping.path.clone())
+        };
+
+        // The mapped files that have executable code are the modules
+        let image_paths: Vec<String> = segments
+            .iter()
+            .filter(|(_, _, flags)| flags & PF_X != 0)
+            .filter_map(|(address, _, _)| path_of(*address))
+            .collect();
+
+        let mut regions: Vec<MemoryRegion> = segments
+            .iter()
+            .map(|(address, size, flags)| {
+                let mut protection = Protection::NONE;
+
+                if flags & PF_R != 0 {
+                    protection = protection | Protection::READ;
+                }
+
+                if flags & PF_W != 0 {
+                    protection = protection | Protection::WRITE;
+                }
+
+                if flags & PF_X != 0 {
+                    protection = protection | Protection::EXECUTE;
+                }
+
+                let path = path_of(*address);
+
+                let kind = match &path {
+                    Some(path) if image_paths.contains(path) => RegionType::Image,
+                    Some(_) => RegionType::Mapped,
+                    None => RegionType::Private,
+                };
+
+                MemoryRegion {
+                    base_address: *address,
+                    size: *size,
+                    protection,
+                    state: if protection == Protection::NONE {
+                        RegionState::Reserved
+                    } else {
+                        RegionState::Committed
+                    },
+                    kind,
+                    path,
+                }
+            })
+            .collect();
+
+        regions.sort_by_key(|region| region.base_address);
+
+        let mut modules: Vec<Module> = Vec::new();
+
+        for region in regions
+            .iter()
+            .filter(|region| region.kind == RegionType::Image)
+        {
+            let path = region.path.clone().unwrap_or_default();
+
+            match modules.iter_mut().find(|module| module.path == path) {
+                Some(module) => module.size = region.end_address() - module.base_address,
+                None => modules.push(Module {
+                    name: path.rsplit('/').next().unwrap_or(&path).to_string(),
+                    path,
+                    base_address: region.base_address,
+                    size: region.size,
+                    entry_point: 0,
+                }),
+            }
+        }
+
+        let main_module_base = modules
+            .iter()
+            .find(|module| entry_point.is_some_and(|entry| module.contains(entry)))
+            .or(modules.first())
+            .map(|module| module.base_address)
+            .unwrap_or(0);
+
+        let mut core = CoreFile {
+            pid,
+            name,
+            threads,
+            modules,
+            main_module_base,
+            regions,
+            memory: FileMemory::new(file, blocks)?,
+        };
+
+        for i in 0..core.modules.len() {
+            core.modules[i].entry_point =
+                elf::read_entry_point(&core, core.modules[i].base_address).unwrap_or(0);
+        }
+
+        Ok(core)
+    }
+}
+
+impl MemoryBackend for CoreFile {
+    fn read_bytes(&self, address: usize, buffer: &mut [u8]) -> Result<usize, Error> {
+        self.memory.read(address, buffer)
+    }
+
+    fn write_bytes(&self, address: usize, _buffer: &[u8]) -> Result<usize, Error> {
+        Err(Error::new(
+            ErrorKind::PermissionDenied,
+            format!("cannot write at {:#x}, a core file is read-only", address),
+        ))
+    }
+
+    fn allocate(&self, _size: usize, _protection: Protection) -> Result<usize, Error> {
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "cannot allocate memory in a core file",
+        ))
+    }
+
+    fn query_region(&self, address: usize) -> Result<MemoryRegion, Error> {
+        Ok(file_format::find_region(&self.regions, address))
+    }
+
+    fn regions(&self) -> Result<Vec<MemoryRegion>, Error> {
+        Ok(self.regions.clone())
+    }
+
+    fn main_module_base(&self) -> usize {
+        self.main_module_base
+    }
+
+    fn modules(&self) -> Result<Vec<Module>, Error> {
+        Ok(self.modules.clone())
+    }
+}
+
+/// Split the content of a PT_NOTE segment into the "CORE" notes.
+///
+/// # Arguments
+/// notes - The content of the segment
+///
+/// # Returns
+/// The type and the descriptor of every "CORE" note (a truncated note ends the list)
+fn parse_notes(notes: &[u8]) -> Vec<(u32, &[u8])> {
+    let mut parsed = Vec::new();
+    let mut offset = 0;
+
+    while let (Ok(name_size), Ok(desc_size), Ok(note_type)) = (
+        u32_at(notes, offset),
+        u32_at(notes, offset + 4),
+        u32_at(notes, offset + 8),
+    ) {
+        let name_offset = offset + 12;
+        let desc_offset = name_offset + (name_size as usize).div_ceil(4) * 4;
+        let desc_end = desc_offset + desc_size as usize;
+
+        let (name, desc) = match (
+            notes.get(name_offset..name_offset + name_size as usize),
+            notes.get(desc_offset..desc_end),
+        ) {
+            (Some(name), Some(desc)) => (name, desc),
+            _ => break,
+        };
+
+        if name == b"CORE\0" {
+            parsed.push((note_type, desc));
+        }
+
+        offset = desc_offset + (desc_size as usize).div_ceil(4) * 4;
+    }
+
+    parsed
+}
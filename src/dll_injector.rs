@@ -1,12 +1,9 @@
-use std::ffi::c_void;
-
 use windows::core::Error;
-use windows::Win32::System::Memory::{PAGE_PROTECTION_FLAGS, VIRTUAL_ALLOCATION_TYPE};
 
+use crate::backend::Protection;
 use crate::memory::{allocate_memory, write_process_memory};
 use crate::process::{create_remote_thread, Process};
 use crate::system::{get_proc_address, load_library};
-use crate::windows_api::constants::{MEM_COMMIT, PAGE_EXECUTE_READWRITE};
 
 /// Inject a DLL into the specified process.
 ///
@@ -23,23 +20,21 @@ pub fn inject_dll(process: &Process, dll_path: &str) -> Result<(), Error> {
     let r_remote_memory = allocate_memory(
         process,
         dll_path_nb_bytes,
-        VIRTUAL_ALLOCATION_TYPE(MEM_COMMIT),
-        PAGE_PROTECTION_FLAGS(PAGE_EXECUTE_READWRITE),
+        Protection::READ | Protection::WRITE | Protection::EXECUTE,
     );
 
     if r_remote_memory.is_err() {
-        return Err(r_remote_memory.unwrap_err());
+        return Err(r_remote_memory.unwrap_err().into());
     }
 
     let remote_memory = r_remote_memory.unwrap();
 
-    let dll_path_buffer = dll_path.as_ptr() as *const c_void;
+    let dll_path_buffer = dll_path.as_bytes_with_nul();
 
-    let r_write_memory =
-        write_process_memory(process, remote_memory, dll_path_buffer, dll_path_nb_bytes);
+    let r_write_memory = write_process_memory(process, remote_memory, dll_path_buffer);
 
     if r_write_memory.is_err() {
-        return Err(r_write_memory.unwrap_err());
+        return Err(r_write_memory.unwrap_err().into());
     }
 
     let r_kernel32 = load_library("kernel32.dll");
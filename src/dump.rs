@@ -0,0 +1,244 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Error, ErrorKind, Read, Seek, Write};
+use std::path::Path;
+
+use crate::backend::{MemoryBackend, MemoryRegion, Module, Protection};
+use crate::file_format::{self, FileBlock, FileMemory};
+
+/// The maximum number of bytes read at once while dumping.
+static DUMP_WINDOW_SIZE: usize = 0x10_0000;
+
+/// The magic of the dump files.
+static DUMP_MAGIC: &[u8; 8] = b"WAPIDUMP";
+
+/// The version of the dump files.
+static DUMP_VERSION: u32 = 1;
+
+/// The tag of the record that ends a dump file.
+const END_RECORD: u8 = 0;
+
+/// The tag of the record that describes a memory region.
+const REGION_RECORD: u8 = 1;
+
+/// The tag of the record that contains the bytes of a range of memory.
+const DATA_RECORD: u8 = 2;
+
+/// Represent the information about the dumped process.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DumpMetadata {
+    /// The process identifier.
+    pub pid: u32,
+
+    /// The name of the process.
+    pub name: String,
+
+    /// The SHA-256 hash of the executable file.
+    pub exe_hash: Vec<u8>,
+
+    /// The base address of the main module.
+    pub main_module_base: usize,
+
+    /// The modules loaded in the process.
+    pub modules: Vec<Module>,
+}
+
+/// Represent a memory dump loaded from a file, usable as a read-only process.
+///
+/// The bytes stay in the file and are read on demand, so big dumps can be opened.
+pub struct MemoryDump {
+    /// The information about the dumped process.
+    pub metadata: DumpMetadata,
+
+    /// The dumped regions (sorted by address).
+    regions: Vec<MemoryRegion>,
+
+    /// The dumped bytes.
+    memory: FileMemory,
+}
+
+impl MemoryDump {
+    /// Open a dump file written by write.
+    ///
+    /// # Arguments
+    /// file - The path of the dump file
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the dump, ready to be read.
+    pub fn load(file: impl AsRef<Path>) -> Result<MemoryDump, Error> {
+        let mut reader = BufReader::new(File::open(file)?);
+
+        file_format::read_header(&mut reader, DUMP_MAGIC, DUMP_VERSION)?;
+
+        let pid = file_format::read_u32(&mut reader)?;
+        let name = file_format::read_string(&mut reader)?;
+        let exe_hash = file_format::read_blob(&mut reader)?;
+        let main_module_base = file_format::read_usize(&mut reader)?;
+
+        let nb_modules = file_format::read_usize(&mut reader)?;
+        let mut modules = Vec::new();
+
+        for _ in 0..nb_modules {
+            modules.push(file_format::read_module(&mut reader)?);
+        }
+
+        let mut regions = Vec::new();
+        let mut blocks = Vec::new();
+
+        loop {
+            let mut tag = [0u8];
+
+            reader.read_exact(&mut tag)?;
+
+            match tag[0] {
+                END_RECORD => break,
+                REGION_RECORD => regions.push(file_format::read_region(&mut reader)?),
+                DATA_RECORD => {
+                    let address = file_format::read_usize(&mut reader)?;
+                    let size = file_format::read_usize(&mut reader)?;
+                    let file_offset = reader.stream_position()?;
+
+                    let skipped = match i64::try_from(size) {
+                        Ok(skipped) => skipped,
+                        Err(_) => {
+                            return Err(Error::new(
+                                ErrorKind::InvalidData,
+                                format!("data record of {:#x} bytes is too big", size),
+                            ))
+                        }
+                    };
+
+                    reader.seek_relative(skipped)?;
+
+                    blocks.push(FileBlock {
+                        address,
+                        size,
+                        file_offset,
+                    });
+                }
+                tag => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!("invalid record {}", tag),
+                    ))
+                }
+            }
+        }
+
+        regions.sort_by_key(|region| region.base_address);
+
+        Ok(MemoryDump {
+            metadata: DumpMetadata {
+                pid,
+                name,
+                exe_hash,
+                main_module_base,
+                modules,
+            },
+            regions,
+            memory: FileMemory::new(reader.into_inner(), blocks)?,
+        })
+    }
+}
+
+impl MemoryBackend for MemoryDump {
+    fn read_bytes(&self, address: usize, buffer: &mut [u8]) -> Result<usize, Error> {
+        self.memory.read(address, buffer)
+    }
+
+    fn write_bytes(&self, address: usize, _buffer: &[u8]) -> Result<usize, Error> {
+        Err(Error::new(
+            ErrorKind::PermissionDenied,
+            format!("cannot write at {:#x}, a memory dump is read-only", address),
+        ))
+    }
+
+    fn allocate(&self, _size: usize, _protection: Protection) -> Result<usize, Error> {
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "cannot allocate memory in a memory dump",
+        ))
+    }
+
+    fn query_region(&self, address: usize) -> Result<MemoryRegion, Error> {
+        Ok(file_format::find_region(&self.regions, address))
+    }
+
+    fn regions(&self) -> Result<Vec<MemoryRegion>, Error> {
+        Ok(self.regions.clone())
+    }
+
+    fn main_module_base(&self) -> usize {
+        self.metadata.main_module_base
+    }
+
+    fn modules(&self) -> Result<Vec<Module>, Error> {
+        Ok(self.metadata.modules.clone())
+    }
+}
+
+/// Write every readable region of the specified process in a dump file.
+///
+/// The parts of the regions that cannot be read are left out of the dump. The file contains a
+/// header (magic "WAPIDUMP" and version), the metadata, then records tagged by a byte: a region
+/// (1), the bytes of a range (2) or the end of the file (0). Every number is little-endian and
+/// the addresses are stored on 64 bits.
+///
+/// # Arguments
+/// process - The process to dump
+/// metadata - The information about the process to store in the dump
+/// file - The path of the dump file
+///
+/// # Returns
+/// If the function succeeds, the return value is nothing.
+pub fn write(
+    process: &(impl MemoryBackend + ?Sized),
+    metadata: &DumpMetadata,
+    file: impl AsRef<Path>,
+) -> Result<(), Error> {
+    let mut writer = BufWriter::new(File::create(file)?);
+
+    file_format::write_header(&mut writer, DUMP_MAGIC, DUMP_VERSION)?;
+    file_format::write_u32(&mut writer, metadata.pid)?;
+    file_format::write_string(&mut writer, &metadata.name)?;
+    file_format::write_blob(&mut writer, &metadata.exe_hash)?;
+    file_format::write_usize(&mut writer, metadata.main_module_base)?;
+
+    file_format::write_usize(&mut writer, metadata.modules.len())?;
+
+    for module in &metadata.modules {
+        file_format::write_module(&mut writer, module)?;
+    }
+
+    let mut buffer = Vec::new();
+
+    for region in process.regions()? {
+        if !region.is_readable() {
+            continue;
+        }
+
+        writer.write_all(&[REGION_RECORD])?;
+        file_format::write_region(&mut writer, &region)?;
+
+        let mut window_address = region.base_address;
+
+        while window_address < region.end_address() {
+            let window_end = region
+                .end_address()
+                .min(window_address.saturating_add(DUMP_WINDOW_SIZE));
+
+            buffer.resize(window_end - window_address, 0);
+
+            if process.read_bytes(window_address, &mut buffer).is_ok() {
+                writer.write_all(&[DATA_RECORD])?;
+                file_format::write_usize(&mut writer, window_address)?;
+                file_format::write_usize(&mut writer, buffer.len())?;
+                writer.write_all(&buffer)?;
+            }
+
+            window_address = window_end;
+        }
+    }
+
+    writer.write_all(&[END_RECORD])?;
+    writer.flush()
+}
@@ -0,0 +1,47 @@
+use std::io::{Error, ErrorKind};
+
+use crate::backend::MemoryBackend;
+
+/// The magic of the ELF files.
+pub(crate) const ELF_MAGIC: &[u8; 4] = b"\x7fELF";
+
+/// e_ident[EI_CLASS] of the 64 bits files.
+pub(crate) const ELF_CLASS_64: u8 = 2;
+
+/// e_type of the position independent images.
+pub(crate) const ET_DYN: u16 = 3;
+
+/// Read the entry point of the ELF image loaded at the specified address.
+///
+/// # Arguments
+/// process - The process that contains the image.
+/// base_address - The address of the ELF header.
+///
+/// # Returns
+/// If the function succeeds, the return value is the absolute address of the entry point (0 if none).
+pub(crate) fn read_entry_point(
+    process: &(impl MemoryBackend + ?Sized),
+    base_address: usize,
+) -> Result<usize, Error> {
+    let mut header = [0u8; 32];
+
+    process.read_bytes(base_address, &mut header)?;
+
+    if &header[0..4] != ELF_MAGIC {
+        return Err(Error::new(ErrorKind::InvalidData, "not an ELF image"));
+    }
+
+    let entry = if header[4] == ELF_CLASS_64 {
+        u64::from_le_bytes(header[24..32].try_into().unwrap()) as usize
+    } else {
+        u32::from_le_bytes(header[24..28].try_into().unwrap()) as usize
+    };
+
+    let e_type = u16::from_le_bytes([header[16], header[17]]);
+
+    if entry == 0 || e_type != ET_DYN {
+        Ok(entry)
+    } else {
+        Ok(base_address + entry)
+    }
+}
@@ -0,0 +1,448 @@
+use std::fs::File;
+use std::io::{Error, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::sync::Mutex;
+
+use crate::backend::{MemoryRegion, Module, Protection, RegionState, RegionType};
+
+/// Represent a range of memory stored in a file.
+pub(crate) struct FileBlock {
+    /// The address of the first byte.
+    pub address: usize,
+
+    /// The number of bytes.
+    pub size: usize,
+
+    /// The position of the first byte in the file.
+    pub file_offset: u64,
+}
+
+/// Represent memory saved in a file (a dump), read on demand so big files can be opened.
+pub(crate) struct FileMemory {
+    /// The ranges of memory stored in the file (sorted by address).
+    blocks: Vec<FileBlock>,
+
+    /// The file that contains the bytes.
+    file: Mutex<File>,
+}
+
+impl FileMemory {
+    /// Create the memory of the specified file.
+    ///
+    /// # Arguments
+    /// file - The file that contains the bytes
+    /// blocks - The ranges of memory stored in the file, in any order
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the created memory. It fails if a range
+    /// overflows the address space or is not entirely in the file.
+    pub(crate) fn new(file: File, mut blocks: Vec<FileBlock>) -> Result<FileMemory, Error> {
+        let file_size = file.metadata()?.len();
+
+        for block in &blocks {
+            if block.address.checked_add(block.size).is_none() {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("block {:#x}+{:#x} overflows", block.address, block.size),
+                ));
+            }
+
+            match block.file_offset.checked_add(block.size as u64) {
+                Some(end) if end <= file_size => {}
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!(
+                            "block {:#x}+{:#x} is not in the file",
+                            block.address, block.size
+                        ),
+                    ))
+                }
+            }
+        }
+
+        blocks.sort_by_key(|block| block.address);
+
+        Ok(FileMemory {
+            blocks,
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Read the bytes at the specified address (a read is never partial).
+    ///
+    /// # Arguments
+    /// self - The memory to read
+    /// address - The address of the first byte
+    /// buffer - The buffer to read into (its length is the number of bytes to read)
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the number of bytes read.
+    pub(crate) fn read(
+        self: &FileMemory,
+        address: usize,
+        buffer: &mut [u8],
+    ) -> Result<usize, Error> {
+        if buffer.is_empty() {
+            return Ok(0);
+        }
+
+        if address.checked_add(buffer.len()).is_none() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("range {:#x}+{:#x} overflows", address, buffer.len()),
+            ));
+        }
+
+        // Find the blocks that cover the whole range before reading anything
+        let mut parts = Vec::new();
+        let mut done = 0;
+        let mut i = self
+            .blocks
+            .partition_point(|block| block.address + block.size <= address);
+
+        while done < buffer.len() {
+            let current = address + done;
+
+            let block = match self.blocks.get(i) {
+                Some(block) if block.address <= current => block,
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::NotFound,
+                        format!("address {:#x} is not in the dump", current),
+                    ))
+                }
+            };
+
+            let length = (block.address + block.size - current).min(buffer.len() - done);
+
+            parts.push((
+                block.file_offset + (current - block.address) as u64,
+                done,
+                length,
+            ));
+
+            done += length;
+            i += 1;
+        }
+
+        let mut file = self.file.lock().unwrap();
+
+        for (file_offset, offset, length) in parts {
+            file.seek(SeekFrom::Start(file_offset))?;
+            file.read_exact(&mut buffer[offset..offset + length])?;
+        }
+
+        Ok(buffer.len())
+    }
+}
+
+/// Find the region that contains the specified address, or the free region around it.
+///
+/// # Arguments
+/// regions - The regions sorted by address
+/// address - The address to look up
+///
+/// # Returns
+/// The region that contains the address
+pub(crate) fn find_region(regions: &[MemoryRegion], address: usize) -> MemoryRegion {
+    let i = regions.partition_point(|region| region.end_address() <= address);
+
+    match regions.get(i) {
+        Some(region) if region.contains(address) => region.clone(),
+        next => {
+            // The free region spans between the surrounding regions
+            let start = match i {
+                0 => 0,
+                _ => regions[i - 1].end_address(),
+            };
+
+            let end = next.map(|region| region.base_address).unwrap_or(usize::MAX);
+
+            MemoryRegion {
+                base_address: start,
+                size: end - start,
+                protection: Protection::NONE,
+                state: RegionState::Free,
+                kind: RegionType::Unknown,
+                path: None,
+            }
+        }
+    }
+}
+
+/// Read the specified number of bytes at the specified position of the file, failing before
+/// allocating anything if they are not all in the file.
+pub(crate) fn read_at(file: &mut File, offset: u64, size: usize) -> Result<Vec<u8>, Error> {
+    match offset.checked_add(size as u64) {
+        Some(end) if end <= file.metadata()?.len() => {}
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("range {:#x}+{:#x} is not in the file", offset, size),
+            ))
+        }
+    }
+
+    let mut bytes = vec![0u8; size];
+
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(&mut bytes)?;
+
+    Ok(bytes)
+}
+
+/// Decode the little-endian u16 at the specified offset of the bytes.
+pub(crate) fn u16_at(bytes: &[u8], offset: usize) -> Result<u16, Error> {
+    Ok(u16::from_le_bytes(field(bytes, offset)?))
+}
+
+/// Decode the little-endian u32 at the specified offset of the bytes.
+pub(crate) fn u32_at(bytes: &[u8], offset: usize) -> Result<u32, Error> {
+    Ok(u32::from_le_bytes(field(bytes, offset)?))
+}
+
+/// Decode the little-endian u64 at the specified offset of the bytes.
+pub(crate) fn u64_at(bytes: &[u8], offset: usize) -> Result<u64, Error> {
+    Ok(u64::from_le_bytes(field(bytes, offset)?))
+}
+
+/// Get the N bytes at the specified offset, failing if the bytes are truncated.
+fn field<const N: usize>(bytes: &[u8], offset: usize) -> Result<[u8; N], Error> {
+    offset
+        .checked_add(N)
+        .and_then(|end| bytes.get(offset..end))
+        .map(|field| field.try_into().unwrap())
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("truncated data at offset {:#x}", offset),
+            )
+        })
+}
+
+/// Write the magic and the version of a file.
+///
+/// # Arguments
+/// writer - The file to write to
+/// magic - The 8 bytes that identify the format
+/// version - The version of the format
+pub(crate) fn write_header(
+    writer: &mut impl Write,
+    magic: &[u8; 8],
+    version: u32,
+) -> Result<(), Error> {
+    writer.write_all(magic)?;
+    write_u32(writer, version)
+}
+
+/// Read and check the magic and the version of a file.
+///
+/// # Arguments
+/// reader - The file to read from
+/// magic - The 8 bytes that identify the format
+/// version - The only supported version of the format
+pub(crate) fn read_header(
+    reader: &mut impl Read,
+    magic: &[u8; 8],
+    version: u32,
+) -> Result<(), Error> {
+    let mut actual = [0u8; 8];
+
+    reader.read_exact(&mut actual)?;
+
+    if &actual != magic {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("not a {} file", String::from_utf8_lossy(magic).trim()),
+        ));
+    }
+
+    let actual_version = read_u32(reader)?;
+
+    if actual_version != version {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "unsupported version {} (expected {})",
+                actual_version, version
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+pub(crate) fn write_u32(writer: &mut impl Write, value: u32) -> Result<(), Error> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+pub(crate) fn read_u32(reader: &mut impl Read) -> Result<u32, Error> {
+    let mut bytes = [0u8; 4];
+
+    reader.read_exact(&mut bytes)?;
+
+    Ok(u32::from_le_bytes(bytes))
+}
+
+pub(crate) fn write_u64(writer: &mut impl Write, value: u64) -> Result<(), Error> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+pub(crate) fn read_u64(reader: &mut impl Read) -> Result<u64, Error> {
+    let mut bytes = [0u8; 8];
+
+    reader.read_exact(&mut bytes)?;
+
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Write an address or a size (always stored on 64 bits).
+pub(crate) fn write_usize(writer: &mut impl Write, value: usize) -> Result<(), Error> {
+    write_u64(writer, value as u64)
+}
+
+/// Read an address or a size, failing if it does not fit in the pointers of this build.
+pub(crate) fn read_usize(reader: &mut impl Read) -> Result<usize, Error> {
+    let value = read_u64(reader)?;
+
+    usize::try_from(value).map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("value {:#x} does not fit in a pointer", value),
+        )
+    })
+}
+
+/// Write a length-prefixed UTF-8 string.
+pub(crate) fn write_string(writer: &mut impl Write, value: &str) -> Result<(), Error> {
+    write_blob(writer, value.as_bytes())
+}
+
+/// Read a length-prefixed UTF-8 string.
+pub(crate) fn read_string(reader: &mut impl Read) -> Result<String, Error> {
+    String::from_utf8(read_blob(reader)?).map_err(|error| Error::new(ErrorKind::InvalidData, error))
+}
+
+/// Write a module (name, path, base address, size and entry point).
+pub(crate) fn write_module(writer: &mut impl Write, module: &Module) -> Result<(), Error> {
+    write_string(writer, &module.name)?;
+    write_string(writer, &module.path)?;
+    write_usize(writer, module.base_address)?;
+    write_usize(writer, module.size)?;
+    write_usize(writer, module.entry_point)
+}
+
+/// Read a module written by write_module.
+pub(crate) fn read_module(reader: &mut impl Read) -> Result<Module, Error> {
+    Ok(Module {
+        name: read_string(reader)?,
+        path: read_string(reader)?,
+        base_address: read_usize(reader)?,
+        size: read_usize(reader)?,
+        entry_point: read_usize(reader)?,
+    })
+}
+
+/// Write length-prefixed bytes.
+pub(crate) fn write_blob(writer: &mut impl Write, value: &[u8]) -> Result<(), Error> {
+    write_u32(writer, value.len() as u32)?;
+    writer.write_all(value)
+}
+
+/// Read length-prefixed bytes.
+pub(crate) fn read_blob(reader: &mut impl Read) -> Result<Vec<u8>, Error> {
+    let length = read_u32(reader)? as usize;
+    let mut bytes = Vec::new();
+
+    reader.take(length as u64).read_to_end(&mut bytes)?;
+
+    if bytes.len() != length {
+        return Err(Error::new(ErrorKind::UnexpectedEof, "truncated data"));
+    }
+
+    Ok(bytes)
+}
+
+/// Write a memory region (base address, size, protection, state, type and optional path).
+pub(crate) fn write_region(writer: &mut impl Write, region: &MemoryRegion) -> Result<(), Error> {
+    let protection = [Protection::READ, Protection::WRITE, Protection::EXECUTE]
+        .iter()
+        .enumerate()
+        .filter(|(_, right)| region.protection.contains(**right))
+        .fold(0u8, |bits, (i, _)| bits | (1 << i));
+
+    let state = match region.state {
+        RegionState::Committed => 0u8,
+        RegionState::Reserved => 1,
+        RegionState::Free => 2,
+    };
+
+    let kind = match region.kind {
+        RegionType::Image => 0u8,
+        RegionType::Mapped => 1,
+        RegionType::Private => 2,
+        RegionType::Unknown => 3,
+    };
+
+    write_usize(writer, region.base_address)?;
+    write_usize(writer, region.size)?;
+    writer.write_all(&[protection, state, kind])?;
+
+    match &region.path {
+        Some(path) => {
+            writer.write_all(&[1])?;
+            write_string(writer, path)
+        }
+        None => writer.write_all(&[0]),
+    }
+}
+
+/// Read a memory region written by write_region.
+pub(crate) fn read_region(reader: &mut impl Read) -> Result<MemoryRegion, Error> {
+    let base_address = read_usize(reader)?;
+    let size = read_usize(reader)?;
+
+    let mut attributes = [0u8; 4];
+
+    reader.read_exact(&mut attributes)?;
+
+    let [protection, state, kind, has_path] = attributes;
+
+    let protection = [Protection::READ, Protection::WRITE, Protection::EXECUTE]
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| protection & (1 << i) != 0)
+        .fold(Protection::NONE, |protection, (_, right)| {
+            protection | *right
+        });
+
+    let state = match state {
+        0 => RegionState::Committed,
+        1 => RegionState::Reserved,
+        2 => RegionState::Free,
+        _ => return Err(Error::new(ErrorKind::InvalidData, "invalid region state")),
+    };
+
+    let kind = match kind {
+        0 => RegionType::Image,
+        1 => RegionType::Mapped,
+        2 => RegionType::Private,
+        3 => RegionType::Unknown,
+        _ => return Err(Error::new(ErrorKind::InvalidData, "invalid region type")),
+    };
+
+    let path = if has_path != 0 {
+        Some(read_string(reader)?)
+    } else {
+        None
+    };
+
+    Ok(MemoryRegion {
+        base_address,
+        size,
+        protection,
+        state,
+        kind,
+        path,
+    })
+}
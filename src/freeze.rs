@@ -0,0 +1,181 @@
+use std::ffi::c_void;
+use std::io::Error;
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::backend::MemoryBackend;
+use crate::memory::{self, MultiLevelPointer};
+use crate::pod::Pod;
+use crate::poll::{PollRegistration, PollTask, Poller};
+
+/// Represent where a frozen value is written.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FreezeTarget {
+    /// The value is at the specified address.
+    Address(usize),
+
+    /// The value is pointed by the multi-level pointer, which is followed before every write.
+    Pointer(MultiLevelPointer),
+}
+
+/// Represent when a frozen value is written.
+pub enum FreezeCondition<T> {
+    /// The value is always written.
+    Always,
+
+    /// The value is written only when the current value is below the specified value.
+    Below(T),
+
+    /// The value is written only when the current value is above the specified value.
+    Above(T),
+
+    /// The value is written only when the function returns true for the current value.
+    When(Box<dyn Fn(&T) -> bool + Send>),
+}
+
+impl<T: PartialOrd> FreezeCondition<T> {
+    /// Check if the condition allows to write, given the current value.
+    fn allows(self: &FreezeCondition<T>, current: &T) -> bool {
+        match self {
+            FreezeCondition::Always => true,
+            FreezeCondition::Below(limit) => current < limit,
+            FreezeCondition::Above(limit) => current > limit,
+            FreezeCondition::When(condition) => condition(current),
+        }
+    }
+}
+
+/// Represent a frozen value that cannot be written anymore (reported once, until it can be
+/// written again).
+#[derive(Debug)]
+pub struct FreezeError {
+    /// The identifier of the freeze (see FreezeGuard).
+    pub id: u64,
+
+    /// Where the value is written.
+    pub target: FreezeTarget,
+
+    /// The error of the failed write (or of the failed read of the pointers).
+    pub error: Error,
+}
+
+/// Write a frozen value at the specified address of the process if its condition allows it.
+type ApplyFreeze = Box<dyn FnMut(&dyn MemoryBackend, usize) -> Result<(), Error> + Send>;
+
+/// Represent a value registered in the freezer.
+struct FreezeTask {
+    /// Where the value is written.
+    target: FreezeTarget,
+
+    /// Write the value if the condition allows it.
+    apply: ApplyFreeze,
+}
+
+impl PollTask for FreezeTask {
+    type Error = FreezeError;
+
+    fn run(process: &dyn MemoryBackend, tasks: &mut [(u64, FreezeTask)]) -> Vec<Result<(), Error>> {
+        tasks
+            .iter_mut()
+            .map(|(_, task)| {
+                // The pointers are followed on every write, the pointed object may have moved
+                let address = match &task.target {
+                    FreezeTarget::Address(address) => *address,
+                    FreezeTarget::Pointer(mlp) => mlp.resolve::<u8>(process, 0)?.address,
+                };
+
+                (task.apply)(process, address)
+            })
+            .collect()
+    }
+
+    fn error(id: u64, task: &FreezeTask, error: Error) -> FreezeError {
+        FreezeError {
+            id,
+            target: task.target.clone(),
+            error,
+        }
+    }
+}
+
+/// Represent a service that keeps values written in the memory of a process from a background
+/// worker thread, each at its own interval, until their guard is dropped.
+///
+/// The worker stops when the freezer is dropped.
+pub struct Freezer {
+    /// The errors of the values that cannot be written anymore.
+    pub errors: Receiver<FreezeError>,
+
+    /// The worker that writes the values.
+    poller: Poller<FreezeTask>,
+}
+
+/// Represent a frozen value, written until the guard is dropped (once dropped, the value is not
+/// written anymore).
+pub struct FreezeGuard {
+    /// The identifier of the frozen value (as reported in the errors).
+    pub id: u64,
+
+    /// The registration of the value in the worker (dropped with the guard).
+    _registration: PollRegistration<FreezeTask>,
+}
+
+impl Freezer {
+    /// Start a freezer (and its worker thread) for the specified process.
+    ///
+    /// # Arguments
+    /// process - The process to write to, shared with the worker
+    ///
+    /// # Returns
+    /// The freezer, without any frozen value
+    pub fn new(process: Arc<impl MemoryBackend + Send + Sync + 'static>) -> Freezer {
+        let (poller, errors) = Poller::new(process);
+
+        Freezer { errors, poller }
+    }
+
+    /// Freeze the specified value: it is written at the target now, then at every interval while
+    /// the condition allows it, until the returned guard is dropped.
+    ///
+    /// # Arguments
+    /// self - The freezer
+    /// target - Where the value is written
+    /// value - The value to write
+    /// interval - The time between two writes
+    /// condition - When the value is written (the current value is read first, except for Always)
+    ///
+    /// # Returns
+    /// The guard that keeps the value frozen
+    pub fn freeze<T: Pod + PartialOrd + Send>(
+        self: &Freezer,
+        target: FreezeTarget,
+        value: T,
+        interval: Duration,
+        condition: FreezeCondition<T>,
+    ) -> FreezeGuard {
+        let apply = move |process: &dyn MemoryBackend, address: usize| -> Result<(), Error> {
+            if !matches!(condition, FreezeCondition::Always) {
+                let current = memory::read::<T>(process, address as *const c_void)?;
+
+                if !condition.allows(&current) {
+                    return Ok(());
+                }
+            }
+
+            memory::write::<T>(process, address as *const c_void, value).map(|_| ())
+        };
+
+        let task = FreezeTask {
+            target,
+            apply: Box::new(apply),
+        };
+
+        let registration = self.poller.register(task, interval);
+
+        FreezeGuard {
+            id: registration.id,
+            _registration: registration,
+        }
+    }
+}
@@ -1,9 +1,33 @@
+// The derive macros refer to this crate as ::wapi, even inside it
+extern crate self as wapi;
+
+pub mod backend;
+pub mod cache;
+pub mod collection;
+pub mod core_file;
+#[cfg(windows)]
 pub mod dll_injector;
+pub mod dump;
+mod elf;
+mod file_format;
+pub mod freeze;
+#[cfg(windows)]
 pub mod handle;
 pub mod memory;
+pub mod minidump;
+pub mod mock;
+pub mod pod;
+pub mod pointer_path;
+pub mod pointer_scan;
+mod poll;
 pub mod process;
+pub mod remote_struct;
+pub mod scan;
+pub mod signature;
+pub mod string;
+#[cfg(windows)]
 pub mod system;
+pub mod watch;
 
-pub mod windows_api {
-    pub mod constants;
-}
+#[cfg(windows)]
+pub mod windows_api;
@@ -1,18 +1,14 @@
-use crate::memory::MultiLevelPointer;
-use crate::process::Process;
-use crate::windows_api::constants::{
+use wapi::memory::{MultiLevelPointer, PointerBase};
+use wapi::process::{self, Process};
+use wapi::remote_struct::RemoteStruct;
+#[cfg(windows)]
+use wapi::windows_api::constants::{
     PROCESS_QUERY_INFORMATION, PROCESS_VM_OPERATION, PROCESS_VM_READ, PROCESS_VM_WRITE,
 };
 
-mod dll_injector;
-mod handle;
-mod memory;
-mod process;
-mod system;
-mod windows_api;
-
 /// This main function is to test directly the library functions without build its.
 /// (This function use re2.exe as target process and the dll path is hardcoded)
+#[cfg(windows)]
 fn main() {
     // Get args from command line
     let args: Vec<String> = std::env::args().collect();
@@ -35,6 +31,29 @@ fn main() {
     get_exec_hash(&process);
     inject_dll(&process, dll_path);
     read_write_multi_level_pointers(&process);
+    read_remote_structs(&process);
+}
+
+/// This main function is to test directly the library functions without build its.
+/// (DLL injection is not available on Linux)
+#[cfg(not(windows))]
+fn main() {
+    // Get args from command line
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() < 2 {
+        println!("Usage: wapi <process_name>");
+        return;
+    }
+
+    let process_name = &args[1];
+
+    let process =
+        process::get_process_by_name(process_name, None, 0).expect("Failed to get game process");
+
+    get_exec_path(&process);
+    get_exec_hash(&process);
+    read_write_multi_level_pointers(&process);
+    read_remote_structs(&process);
 }
 
 fn get_exec_path(process: &Process) {
@@ -49,14 +68,35 @@ fn get_exec_hash(process: &Process) {
     println!("Successfully read exec hash: {:?}", exec_hash);
 }
 
+#[cfg(windows)]
 fn inject_dll(process: &Process, dll_path: &str) {
-    dll_injector::inject_dll(&process, dll_path).expect("Failed to inject dll");
+    wapi::dll_injector::inject_dll(process, dll_path).expect("Failed to inject dll");
 
     println!("Successfully injected DLL into target process");
 }
 
+/// The hit points of the player of re2.exe.
+#[derive(Debug, RemoteStruct)]
+struct PlayerHitPoint {
+    #[offset(0x54)]
+    max_hp: i32,
+
+    #[offset(0x58)]
+    hp: i32,
+}
+
+/// The condition of the player of re2.exe.
+#[derive(Debug, RemoteStruct)]
+struct PlayerCondition {
+    #[offset(0x230)]
+    #[pointer]
+    #[nested]
+    hit_point: PlayerHitPoint,
+}
+
 fn read_write_multi_level_pointers(process: &Process) {
     let player_condition_ptr = MultiLevelPointer {
+        base: PointerBase::MainModule,
         base_address: 0x091AD2C0,
         offsets: vec![0x50, 0x10, 0x20],
     };
@@ -79,3 +119,21 @@ fn read_write_multi_level_pointers(process: &Process) {
 
     println!("Successfully read and write in memory with multi-level pointers");
 }
+
+fn read_remote_structs(process: &Process) {
+    let player_condition_ptr = MultiLevelPointer {
+        base: PointerBase::MainModule,
+        base_address: 0x091AD2C0,
+        offsets: vec![0x50, 0x10, 0x20],
+    };
+
+    let player_condition_address = player_condition_ptr.read::<usize>(process, 0x0).unwrap();
+    let player_condition = PlayerCondition::read_from(process, player_condition_address).unwrap();
+
+    println!(
+        "HP: {} / {}",
+        player_condition.hit_point.hp, player_condition.hit_point.max_hp
+    );
+
+    println!("Successfully read remote structs");
+}
@@ -1,24 +1,211 @@
 use std::ffi::c_void;
+use std::fmt;
+use std::io::{Error, ErrorKind};
+use std::marker::PhantomData;
+use std::path::Path;
 
-use windows::core::Error;
-use windows::Win32::System::Diagnostics::Debug::{ReadProcessMemory, WriteProcessMemory};
-use windows::Win32::System::Memory::{
-    VirtualAllocEx, PAGE_PROTECTION_FLAGS, VIRTUAL_ALLOCATION_TYPE,
-};
+use crate::backend::{MemoryBackend, MemoryRegion, Protection, RegionState};
+use crate::dump::{self, DumpMetadata};
+use crate::pod::{self, Pod, Validated};
+use crate::process::{self, Process};
+use crate::signature::Signature;
+use crate::string::{self, StringLayout};
 
-use crate::process::Process;
+/// The maximum number of bytes between two requests of a scatter read that are read at once.
+static SCATTER_MAX_GAP: usize = 0x100;
+
+/// The maximum number of bytes of a coalesced read of a scatter read.
+static SCATTER_MAX_READ: usize = 0x10_0000;
+
+/// Represent what the base address of a multi-level pointer is relative to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PointerBase {
+    /// The base address is an absolute address.
+    Absolute,
+
+    /// The base address is an offset from the main module (the executable).
+    MainModule,
+
+    /// The base address is an offset from the module with the specified file name (case-insensitive).
+    Module(String),
+
+    /// The base address is an offset from the address resolved by the specified signature.
+    Signature(Signature),
+}
 
 /// Represent a multi-level pointer.
 ///
 /// # Fields
+/// base - What the base address is relative to (resolved each time the pointer is followed).
 /// base_address - The base address of the pointer.
 /// offsets - The offsets to apply to the base address.
-/// struct_offset - The offset to apply to the final address (usefull to read a specific property of a struct).
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct MultiLevelPointer {
+    pub base: PointerBase,
     pub base_address: usize,
     pub offsets: Vec<usize>,
 }
 
+/// Represent a pointer read while following a multi-level pointer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PointerHop {
+    /// The address where the pointer was read.
+    pub address: usize,
+
+    /// The value of the pointer.
+    pub value: usize,
+}
+
+/// Represent the pointers read while following a multi-level pointer to its final address.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PointerTrace {
+    /// The resolved base address (the address of the first pointer).
+    pub base_address: usize,
+
+    /// The pointers read, in order.
+    pub hops: Vec<PointerHop>,
+
+    /// The final address (the address of the pointed value).
+    pub address: usize,
+}
+
+/// Represent why a hop of a multi-level pointer failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HopFailure {
+    /// The base address cannot be resolved (module not loaded, signature not found...).
+    Base,
+
+    /// The address of the pointer is not mapped.
+    Unmapped,
+
+    /// The address of the pointer is mapped but cannot be read (no read access, guard page...).
+    Unreadable,
+
+    /// The pointer is null.
+    Null,
+}
+
+impl fmt::Display for HopFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HopFailure::Base => write!(f, "the base address cannot be resolved"),
+            HopFailure::Unmapped => write!(f, "the address is not mapped"),
+            HopFailure::Unreadable => write!(f, "the address cannot be read"),
+            HopFailure::Null => write!(f, "the pointer is null"),
+        }
+    }
+}
+
+/// Represent the failure of a multi-level pointer to be followed, with the hops that succeeded.
+#[derive(Debug)]
+pub struct PointerTraceError {
+    /// The index of the failing hop (0 is the pointer at the base address).
+    pub hop: usize,
+
+    /// The address of the failing hop (the unresolved base address for a Base failure).
+    pub address: usize,
+
+    /// Why the hop failed.
+    pub failure: HopFailure,
+
+    /// The pointers read before the failure (with the null pointer for a Null failure).
+    pub hops: Vec<PointerHop>,
+
+    /// The error returned by the backend.
+    pub error: Error,
+}
+
+impl fmt::Display for PointerTraceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "hop {} at {:#x} failed: {} ({})",
+            self.hop, self.address, self.failure, self.error
+        )
+    }
+}
+
+impl std::error::Error for PointerTraceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl From<PointerTraceError> for Error {
+    fn from(error: PointerTraceError) -> Error {
+        Error::new(error.error.kind(), error.to_string())
+    }
+}
+
+/// Represent the address of a value of a multi-level pointer, resolved once to be read and
+/// written many times without following the pointers again.
+///
+/// The last pointer of the chain is kept, so is_stale can tell if the object that contains the
+/// value moved (the pointer was changed) without following the whole chain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResolvedAddress<T: Pod> {
+    /// The address of the value.
+    pub address: usize,
+
+    /// The address of the last pointer of the chain.
+    pub pointer_address: usize,
+
+    /// The value of the last pointer when the address was resolved.
+    pub pointer: usize,
+
+    /// The type of the value.
+    value_type: PhantomData<fn() -> T>,
+}
+
+impl<T: Pod> ResolvedAddress<T> {
+    /// Read the value.
+    ///
+    /// # Arguments
+    /// self - The resolved address
+    /// process - The process that contains the value
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the read value
+    pub fn read(
+        self: &ResolvedAddress<T>,
+        process: &(impl MemoryBackend + ?Sized),
+    ) -> Result<T, Error> {
+        read::<T>(process, self.address as *const c_void)
+    }
+
+    /// Write the specified value.
+    ///
+    /// # Arguments
+    /// self - The resolved address
+    /// process - The process that contains the value
+    /// value - The value to write
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the number of bytes written
+    pub fn write(
+        self: &ResolvedAddress<T>,
+        process: &(impl MemoryBackend + ?Sized),
+        value: T,
+    ) -> Result<usize, Error> {
+        write::<T>(process, self.address as *const c_void, value)
+    }
+
+    /// Check if the object that contains the value moved since the address was resolved, by
+    /// reading the last pointer of the chain again (the pointers before it are not checked).
+    ///
+    /// # Arguments
+    /// self - The resolved address
+    /// process - The process that contains the pointers
+    ///
+    /// # Returns
+    /// True if the last pointer changed or cannot be read anymore (the multi-level pointer must be
+    /// resolved again)
+    pub fn is_stale(self: &ResolvedAddress<T>, process: &(impl MemoryBackend + ?Sized)) -> bool {
+        read::<usize>(process, self.pointer_address as *const c_void)
+            .map_or(true, |pointer| pointer != self.pointer)
+    }
+}
+
 impl MultiLevelPointer {
     /// Create a new multi-level pointer from another by adding the specified offsets.
     ///
@@ -34,11 +221,153 @@ impl MultiLevelPointer {
         new_offsets.extend(offsets);
 
         MultiLevelPointer {
+            base: multi_level_pointer.base.clone(),
             base_address: multi_level_pointer.base_address,
             offsets: new_offsets,
         }
     }
 
+    /// Compute the absolute address of the first pointer of the chain.
+    ///
+    /// The module or the signature of the base is looked up at each call, so the same multi-level
+    /// pointer survives ASLR and module reloads (a signature base scans its module each time).
+    ///
+    /// # Arguments
+    /// self - The multi-level pointer
+    /// process - The process that contains the first pointer
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the absolute base address.
+    pub fn resolve_base(
+        self: &MultiLevelPointer,
+        process: &(impl MemoryBackend + ?Sized),
+    ) -> Result<usize, Error> {
+        let base = match &self.base {
+            PointerBase::Absolute => 0,
+            PointerBase::MainModule => process.main_module_base(),
+            PointerBase::Module(name) => process
+                .modules()?
+                .iter()
+                .find(|module| module.name.eq_ignore_ascii_case(name))
+                .map(|module| module.base_address)
+                .ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::NotFound,
+                        format!("module {} is not loaded", name),
+                    )
+                })?,
+            PointerBase::Signature(signature) => signature.resolve(process)?,
+        };
+
+        Ok(base.wrapping_add(self.base_address))
+    }
+
+    /// Follow the multi-level pointer once to get the address of the pointed value.
+    ///
+    /// # Arguments
+    /// self - The multi-level pointer
+    /// process - The process that contains the pointers
+    /// offset - The last offset to apply
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the address, to read or write the value
+    /// without following the pointers again. It fails like resolve_traced, on a null pointer too.
+    pub fn resolve<T: Pod>(
+        self: &MultiLevelPointer,
+        process: &(impl MemoryBackend + ?Sized),
+        offset: usize,
+    ) -> Result<ResolvedAddress<T>, Error> {
+        let trace = self.resolve_traced(process, offset)?;
+
+        // There is always a hop: the pointer at the base is read even without offsets
+        let last = trace.hops[trace.hops.len() - 1];
+
+        Ok(ResolvedAddress {
+            address: trace.address,
+            pointer_address: last.address,
+            pointer: last.value,
+            value_type: PhantomData,
+        })
+    }
+
+    /// Follow the multi-level pointer like read does, recording every pointer read.
+    ///
+    /// # Arguments
+    /// self - The multi-level pointer
+    /// process - The process that contains the pointers
+    /// offset - The last offset to apply
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the pointers read and the final address. If
+    /// it fails, the error tells which hop failed, at which address and why.
+    pub fn resolve_traced(
+        self: &MultiLevelPointer,
+        process: &(impl MemoryBackend + ?Sized),
+        offset: usize,
+    ) -> Result<PointerTrace, PointerTraceError> {
+        let base_address = self
+            .resolve_base(process)
+            .map_err(|error| PointerTraceError {
+                hop: 0,
+                address: self.base_address,
+                failure: HopFailure::Base,
+                hops: Vec::new(),
+                error,
+            })?;
+
+        // The pointers are read at the base then at every offset but the last
+        let (followed, last) = match self.offsets.split_last() {
+            None => (&[][..], offset),
+            Some((last, offsets)) => (offsets, last.wrapping_add(offset)),
+        };
+
+        let mut hops = Vec::new();
+        let mut address = base_address;
+
+        for hop in 0..=followed.len() {
+            let value = match read::<usize>(process, address as *const c_void) {
+                Ok(value) => value,
+                Err(error) => {
+                    let failure = match process.query_region(address) {
+                        Ok(region) if region.state != RegionState::Free => HopFailure::Unreadable,
+                        _ => HopFailure::Unmapped,
+                    };
+
+                    return Err(PointerTraceError {
+                        hop,
+                        address,
+                        failure,
+                        hops,
+                        error,
+                    });
+                }
+            };
+
+            hops.push(PointerHop { address, value });
+
+            if value == 0 {
+                return Err(PointerTraceError {
+                    hop,
+                    address,
+                    failure: HopFailure::Null,
+                    hops,
+                    error: Error::new(
+                        ErrorKind::InvalidData,
+                        format!("null pointer at {:#x}", address),
+                    ),
+                });
+            }
+
+            address = value.wrapping_add(followed.get(hop).copied().unwrap_or(last));
+        }
+
+        Ok(PointerTrace {
+            base_address,
+            hops,
+            address,
+        })
+    }
+
     /// Read the value of specified type pointed by the multi-level pointer.
     ///
     /// # Arguments
@@ -48,8 +377,33 @@ impl MultiLevelPointer {
     ///
     /// # Returns
     /// If the function succeeds, the return value is the read value
-    pub fn read<T>(self: &MultiLevelPointer, process: &Process, offset: usize) -> Result<T, Error> {
-        read_multi_level_pointer::<T>(&process, self, offset)
+    pub fn read<T: Pod>(
+        self: &MultiLevelPointer,
+        process: &(impl MemoryBackend + ?Sized),
+        offset: usize,
+    ) -> Result<T, Error> {
+        read_multi_level_pointer::<T>(process, self, offset)
+    }
+
+    /// Read the string pointed by the multi-level pointer.
+    ///
+    /// # Arguments
+    /// self - The multi-level pointer to read
+    /// process - The process that contains the pointed string to read
+    /// offset - The last offset to apply to read the string
+    /// layout - How the string is stored
+    /// max_length - The maximum number of characters (code units) of the string
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the read string
+    pub fn read_string(
+        self: &MultiLevelPointer,
+        process: &(impl MemoryBackend + ?Sized),
+        offset: usize,
+        layout: StringLayout,
+        max_length: usize,
+    ) -> Result<String, Error> {
+        read_multi_level_pointer_string(process, self, offset, layout, max_length)
     }
 
     /// Write the specified value at the pointed memory by the multi-level pointer.
@@ -61,13 +415,13 @@ impl MultiLevelPointer {
     ///
     /// # Returns
     /// If the function succeeds, the return value is the number of bytes written
-    pub fn write<T>(
+    pub fn write<T: Pod>(
         self: &MultiLevelPointer,
-        process: &Process,
+        process: &(impl MemoryBackend + ?Sized),
         offset: usize,
         value: T,
     ) -> Result<usize, Error> {
-        write_multi_level_pointer::<T>(&process, self, offset, value)
+        write_multi_level_pointer::<T>(process, self, offset, value)
     }
 }
 
@@ -76,30 +430,16 @@ impl MultiLevelPointer {
 /// # Arguments
 /// process - The process to read from.
 /// ptr - The address to read from.
-/// buffer - The buffer to read into.
-/// size - The size of the buffer.
+/// buffer - The buffer to read into (its length is the number of bytes to read).
 ///
 /// # Returns
 /// If the function succeeds, the return value is the number of bytes read from the specified process.
 pub fn read_process_memory(
-    process: &Process,
+    process: &(impl MemoryBackend + ?Sized),
     ptr: *const c_void,
-    buffer: *mut c_void,
-    size: usize,
+    buffer: &mut [u8],
 ) -> Result<usize, Error> {
-    let mut lp_number_of_bytes_read = 0;
-
-    unsafe {
-        ReadProcessMemory(
-            process.handle,
-            ptr,
-            buffer,
-            size,
-            Some(&mut lp_number_of_bytes_read),
-        )?
-    };
-
-    Ok(lp_number_of_bytes_read)
+    process.read_bytes(ptr as usize, buffer)
 }
 
 /// Read the value at the specified address (ptr) from the process memory.
@@ -110,15 +450,33 @@ pub fn read_process_memory(
 ///
 /// # Returns
 /// If the function succeeds, the return value is the value read from the specified process.
-pub fn read<T>(process: &Process, ptr: *const c_void) -> Result<T, Error> {
-    let mut buffer: T = unsafe { std::mem::zeroed() };
-    let size = std::mem::size_of::<T>();
+pub fn read<T: Pod>(
+    process: &(impl MemoryBackend + ?Sized),
+    ptr: *const c_void,
+) -> Result<T, Error> {
+    let mut buffer = pod::zeroed::<T>();
 
-    read_process_memory(process, ptr, &mut buffer as *mut T as *mut c_void, size)?;
+    read_process_memory(process, ptr, pod::bytes_of_mut(&mut buffer))?;
 
     Ok(buffer)
 }
 
+/// Read the value at the specified address (ptr) from the process memory and validate it.
+///
+/// # Arguments
+/// process - The process to read from.
+/// ptr - The address to read from.
+///
+/// # Returns
+/// If the function succeeds, the return value is the value read from the specified process. It
+/// fails with InvalidData if the bytes are not a valid value of the type.
+pub fn read_validated<T: Validated>(
+    process: &(impl MemoryBackend + ?Sized),
+    ptr: *const c_void,
+) -> Result<T, Error> {
+    T::from_raw(read::<T::Raw>(process, ptr)?)
+}
+
 /// Read the value at the specified multi-level pointer from the process memory.
 ///
 /// # Arguments
@@ -128,27 +486,138 @@ pub fn read<T>(process: &Process, ptr: *const c_void) -> Result<T, Error> {
 ///
 /// # Returns
 /// If the function succeeds, the return value is the value read from the specified process.
-pub fn read_multi_level_pointer<T>(
-    process: &Process,
+pub fn read_multi_level_pointer<T: Pod>(
+    process: &(impl MemoryBackend + ?Sized),
     mlp: &MultiLevelPointer,
     offset: usize,
 ) -> Result<T, Error> {
-    let mut ptr = read::<usize>(
-        &process,
-        (process.module_handle.0 + mlp.base_address as isize) as *const c_void,
-    )?;
-
-    if mlp.offsets.len() == 0 {
-        ptr = ptr + offset;
-    } else {
-        for i in 0..mlp.offsets.len() - 1 {
-            ptr = read::<usize>(&process, (ptr + mlp.offsets[i]) as *const c_void)?;
+    mlp.resolve::<T>(process, offset)?.read(process)
+}
+
+/// Read the string at the specified multi-level pointer from the process memory.
+///
+/// # Arguments
+/// process - The process to read from.
+/// mlp - The multi-level pointer to read from.
+/// offset - The last offset to apply to read the string
+/// layout - How the string is stored
+/// max_length - The maximum number of characters (code units) of the string
+///
+/// # Returns
+/// If the function succeeds, the return value is the string read from the specified process.
+pub fn read_multi_level_pointer_string(
+    process: &(impl MemoryBackend + ?Sized),
+    mlp: &MultiLevelPointer,
+    offset: usize,
+    layout: StringLayout,
+    max_length: usize,
+) -> Result<String, Error> {
+    let resolved = mlp.resolve::<u8>(process, offset)?;
+
+    string::read_string(process, resolved.address, layout, max_length)
+}
+
+/// Read several ranges of the process memory with the fewest possible reads.
+///
+/// The requests that are close to each other (overlapping, adjacent or separated by a few bytes)
+/// are coalesced into a single read, and every coalesced read is issued at once (a single
+/// process_vm_readv on Linux). When a coalesced read fails, its requests are read again one by
+/// one so each of them gets its own result.
+///
+/// # Arguments
+/// process - The process to read from.
+/// requests - The (address, length) of the ranges to read, in any order.
+///
+/// # Returns
+/// The bytes or the error of every request, in the same order as the requests.
+pub fn read_scatter(
+    process: &(impl MemoryBackend + ?Sized),
+    requests: &[(usize, usize)],
+) -> Vec<Result<Vec<u8>, Error>> {
+    let mut results: Vec<Option<Result<Vec<u8>, Error>>> = requests.iter().map(|_| None).collect();
+
+    let mut order: Vec<usize> = (0..requests.len()).collect();
+
+    order.sort_by_key(|i| requests[*i].0);
+
+    // The coalesced reads as (start, end, indexes of the requests)
+    let mut ranges: Vec<(usize, usize, Vec<usize>)> = Vec::new();
+
+    for i in order {
+        let (address, length) = requests[i];
+
+        let end = match address.checked_add(length) {
+            Some(end) => end,
+            None => {
+                results[i] = Some(Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("range {:#x}+{:#x} overflows", address, length),
+                )));
+                continue;
+            }
+        };
+
+        match ranges.last_mut() {
+            Some((start, range_end, members))
+                if address <= range_end.saturating_add(SCATTER_MAX_GAP)
+                    && end.max(*range_end) - *start <= SCATTER_MAX_READ =>
+            {
+                *range_end = end.max(*range_end);
+                members.push(i);
+            }
+            _ => ranges.push((address, end, vec![i])),
         }
+    }
+
+    let mut buffers: Vec<Vec<u8>> = ranges
+        .iter()
+        .map(|(start, end, _)| vec![0; end - start])
+        .collect();
+
+    let mut reads: Vec<(usize, &mut [u8])> = ranges
+        .iter()
+        .zip(buffers.iter_mut())
+        .map(|((start, _, _), buffer)| (*start, buffer.as_mut_slice()))
+        .collect();
+
+    let range_results = process.read_many(&mut reads);
+
+    let mut retried = Vec::new();
 
-        ptr = ptr + (mlp.offsets[mlp.offsets.len() - 1] + offset);
+    for (((start, _, members), buffer), result) in ranges.iter().zip(&buffers).zip(range_results) {
+        match result {
+            Ok(_) => {
+                for i in members {
+                    let (address, length) = requests[*i];
+                    let offset = address - start;
+
+                    results[*i] = Some(Ok(buffer[offset..offset + length].to_vec()));
+                }
+            }
+            Err(error) if members.len() == 1 => results[members[0]] = Some(Err(error)),
+            Err(_) => retried.extend(members.iter().copied()),
+        }
+    }
+
+    if retried.is_empty() {
+        return results.into_iter().map(|result| result.unwrap()).collect();
     }
 
-    return read::<T>(&process, ptr as *const c_void);
+    let mut retry_buffers: Vec<Vec<u8>> = retried.iter().map(|i| vec![0; requests[*i].1]).collect();
+
+    let mut reads: Vec<(usize, &mut [u8])> = retried
+        .iter()
+        .zip(retry_buffers.iter_mut())
+        .map(|(i, buffer)| (requests[*i].0, buffer.as_mut_slice()))
+        .collect();
+
+    let retry_results = process.read_many(&mut reads);
+
+    for ((i, buffer), result) in retried.iter().zip(retry_buffers).zip(retry_results) {
+        results[*i] = Some(result.map(|_| buffer));
+    }
+
+    results.into_iter().map(|result| result.unwrap()).collect()
 }
 
 /// Write the specified buffer in the memory of the specified process at the specified address.
@@ -156,30 +625,16 @@ pub fn read_multi_level_pointer<T>(
 /// # Arguments
 /// process - The process to write to.
 /// ptr - The address to write to.
-/// buffer - The buffer to write from.
-/// size - The size of the buffer.
+/// buffer - The buffer to write from (its length is the number of bytes to write).
 ///
 /// # Returns
 /// If the function succeeds, the return value is the number of bytes written in the specified process.
 pub fn write_process_memory(
-    process: &Process,
+    process: &(impl MemoryBackend + ?Sized),
     ptr: *const c_void,
-    buffer: *const c_void,
-    size: usize,
+    buffer: &[u8],
 ) -> Result<usize, Error> {
-    let mut lp_number_of_bytes_written = 0;
-
-    unsafe {
-        WriteProcessMemory(
-            process.handle,
-            ptr,
-            buffer,
-            size,
-            Some(&mut lp_number_of_bytes_written),
-        )?
-    };
-
-    Ok(lp_number_of_bytes_written)
+    process.write_bytes(ptr as usize, buffer)
 }
 
 /// Write the specified value in the memory of the specified process at the specified address.
@@ -191,12 +646,29 @@ pub fn write_process_memory(
 ///
 /// # Returns
 /// If the function succeeds, the return value is the number of bytes written in the specified process.
-pub fn write<T>(process: &Process, ptr: *const c_void, value: T) -> Result<usize, Error> {
-    let size = std::mem::size_of::<T>();
-
-    let result = write_process_memory(process, ptr, &value as *const T as *const c_void, size)?;
+pub fn write<T: Pod>(
+    process: &(impl MemoryBackend + ?Sized),
+    ptr: *const c_void,
+    value: T,
+) -> Result<usize, Error> {
+    write_process_memory(process, ptr, pod::bytes_of(&value))
+}
 
-    Ok(result)
+/// Write the specified value, converted to its raw bits, at the specified address (ptr).
+///
+/// # Arguments
+/// process - The process to write to.
+/// ptr - The address to write to.
+/// value - The value to write.
+///
+/// # Returns
+/// If the function succeeds, the return value is the number of bytes written in the specified process.
+pub fn write_validated<T: Validated>(
+    process: &(impl MemoryBackend + ?Sized),
+    ptr: *const c_void,
+    value: &T,
+) -> Result<usize, Error> {
+    write::<T::Raw>(process, ptr, value.to_raw())
 }
 
 /// Write the specified value at the specified multi-level pointer from the process memory.
@@ -209,28 +681,26 @@ pub fn write<T>(process: &Process, ptr: *const c_void, value: T) -> Result<usize
 ///
 /// # Returns
 /// If the function succeeds, the return value is the number of bytes written in the specified process.
-pub fn write_multi_level_pointer<T>(
-    process: &Process,
+pub fn write_multi_level_pointer<T: Pod>(
+    process: &(impl MemoryBackend + ?Sized),
     mlp: &MultiLevelPointer,
     offset: usize,
     value: T,
 ) -> Result<usize, Error> {
-    let mut ptr = read::<usize>(
-        &process,
-        (process.module_handle.0 + mlp.base_address as isize) as *const c_void,
-    )?;
-
-    if mlp.offsets.len() == 0 {
-        ptr = ptr + offset;
-    } else {
-        for i in 0..mlp.offsets.len() - 1 {
-            ptr = read::<usize>(&process, (ptr + mlp.offsets[i]) as *const c_void)?;
-        }
-
-        ptr = ptr + (mlp.offsets[mlp.offsets.len() - 1] + offset);
-    }
+    mlp.resolve::<T>(process, offset)?.write(process, value)
+}
 
-    return write::<T>(&process, ptr as *const c_void, value);
+/// Enumerate the memory regions of the specified process (free regions are skipped).
+///
+/// # Arguments
+/// process - The process to inspect.
+///
+/// # Returns
+/// If the function succeeds, the return value is an iterator over the regions sorted by address.
+pub fn regions(
+    process: &(impl MemoryBackend + ?Sized),
+) -> Result<impl Iterator<Item = MemoryRegion>, Error> {
+    Ok(process.regions()?.into_iter())
 }
 
 /// Allocate memory in the specified process.
@@ -238,23 +708,39 @@ pub fn write_multi_level_pointer<T>(
 /// # Arguments
 /// process - The process to allocate memory in.
 /// size - The size of the memory to allocate.
-/// fl_allocation_type - The type of memory allocation.
-/// fl_protect - The memory protection for the region of pages to be allocated
+/// protection - The memory protection for the region of pages to be allocated
 ///
 /// # Returns
 /// If the function succeeds, the return value is the base address of the allocated memory.
 pub fn allocate_memory(
-    process: &Process,
+    process: &(impl MemoryBackend + ?Sized),
     size: usize,
-    fl_allocation_type: VIRTUAL_ALLOCATION_TYPE,
-    fl_protect: PAGE_PROTECTION_FLAGS,
+    protection: Protection,
 ) -> Result<*mut c_void, Error> {
-    let lp_base_address =
-        unsafe { VirtualAllocEx(process.handle, None, size, fl_allocation_type, fl_protect) };
+    let lp_base_address = process.allocate(size, protection)?;
+
+    Ok(lp_base_address as *mut c_void)
+}
 
-    return if lp_base_address.is_null() {
-        Err(Error::from_win32())
-    } else {
-        Ok(lp_base_address)
+/// Dump every readable region of the specified process in a file, with its metadata (pid, name,
+/// executable hash, modules and region protections).
+///
+/// The dump can be opened with dump::MemoryDump::load and read like the live process.
+///
+/// # Arguments
+/// process - The process to dump.
+/// file - The path of the dump file.
+///
+/// # Returns
+/// If the function succeeds, the return value is nothing.
+pub fn dump(process: &Process, file: impl AsRef<Path>) -> Result<(), Error> {
+    let metadata = DumpMetadata {
+        pid: process.pid,
+        name: process.name.clone(),
+        exe_hash: process::get_hash(process)?,
+        main_module_base: process.main_module_base(),
+        modules: process.modules()?,
     };
+
+    dump::write(process, &metadata, file)
 }
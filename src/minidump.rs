@@ -0,0 +1,704 @@
+use std::fs::File;
+use std::io::{BufWriter, Error, ErrorKind, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::backend::{MemoryBackend, MemoryRegion, Module, Protection, RegionState, RegionType};
+use crate::file_format::{self, read_at, u32_at, u64_at, FileBlock, FileMemory};
+use crate::process::{self, Process};
+
+/// The signature of a minidump ("MDMP").
+const MINIDUMP_SIGNATURE: u32 = 0x504D_444D;
+
+/// The version of the minidump format.
+const MINIDUMP_VERSION: u32 = 0xA793;
+
+/// The types of the streams written and read.
+const THREAD_LIST_STREAM: u32 = 3;
+const MODULE_LIST_STREAM: u32 = 4;
+const MEMORY_LIST_STREAM: u32 = 5;
+const SYSTEM_INFO_STREAM: u32 = 7;
+const MEMORY_64_LIST_STREAM: u32 = 9;
+const MEMORY_INFO_LIST_STREAM: u32 = 16;
+
+/// The sizes of the fixed-size structures of the format.
+const HEADER_SIZE: usize = 32;
+const DIRECTORY_ENTRY_SIZE: usize = 12;
+const SYSTEM_INFO_SIZE: usize = 56;
+const MODULE_SIZE: usize = 108;
+const THREAD_SIZE: usize = 48;
+const MEMORY_DESCRIPTOR_SIZE: usize = 16;
+const MEMORY_INFO_LIST_HEADER_SIZE: usize = 16;
+const MEMORY_INFO_SIZE: usize = 48;
+
+/// The memory states, types and protections of the memory info list (the Windows values).
+const MEM_COMMIT: u32 = 0x1000;
+const MEM_RESERVE: u32 = 0x2000;
+const MEM_FREE: u32 = 0x1_0000;
+const MEM_PRIVATE: u32 = 0x2_0000;
+const MEM_MAPPED: u32 = 0x4_0000;
+const MEM_IMAGE: u32 = 0x100_0000;
+const PAGE_NOACCESS: u32 = 0x01;
+const PAGE_READONLY: u32 = 0x02;
+const PAGE_READWRITE: u32 = 0x04;
+const PAGE_WRITECOPY: u32 = 0x08;
+const PAGE_EXECUTE: u32 = 0x10;
+const PAGE_EXECUTE_READ: u32 = 0x20;
+const PAGE_EXECUTE_READWRITE: u32 = 0x40;
+const PAGE_EXECUTE_WRITECOPY: u32 = 0x80;
+
+/// The maximum number of bytes read at once while dumping.
+static MINIDUMP_WINDOW_SIZE: usize = 0x10_0000;
+
+/// Represent a thread of a minidump.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MinidumpThread {
+    /// The thread identifier.
+    pub id: u32,
+
+    /// The address of the thread environment block (0 if unknown).
+    pub teb: u64,
+
+    /// The start address of the stack of the thread (0 if unknown).
+    pub stack_address: u64,
+}
+
+/// Represent a minidump loaded from a file, usable as a read-only process.
+///
+/// The memory list and the 64 bits memory list are supported, the memory info list (if present)
+/// gives the regions and their protections.
+pub struct Minidump {
+    /// The modules of the dumped process (the first one is the executable).
+    pub modules: Vec<Module>,
+
+    /// The threads of the dumped process.
+    pub threads: Vec<MinidumpThread>,
+
+    /// The regions of the dumped process (sorted by address).
+    regions: Vec<MemoryRegion>,
+
+    /// The dumped bytes.
+    memory: FileMemory,
+}
+
+impl Minidump {
+    /// Open a minidump file.
+    ///
+    /// # Arguments
+    /// file - The path of the minidump file
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the minidump, ready to be read.
+    pub fn load(file: impl AsRef<Path>) -> Result<Minidump, Error> {
+        let mut file = File::open(file)?;
+
+        let header = read_at(&mut file, 0, HEADER_SIZE)?;
+
+        if u32_at(&header, 0)? != MINIDUMP_SIGNATURE {
+            return Err(Error::new(ErrorKind::InvalidData, "not a minidump file"));
+        }
+
+        let nb_streams = u32_at(&header, 8)? as usize;
+        let directory_rva = u32_at(&header, 12)? as u64;
+
+        let directory_size = match nb_streams.checked_mul(DIRECTORY_ENTRY_SIZE) {
+            Some(directory_size) => directory_size,
+            None => return Err(Error::new(ErrorKind::InvalidData, "too many streams")),
+        };
+        let directory = read_at(&mut file, directory_rva, directory_size)?;
+
+        let mut modules = Vec::new();
+        let mut threads = Vec::new();
+        let mut regions = Vec::new();
+        let mut blocks = Vec::new();
+
+        for i in 0..nb_streams {
+            let entry = i * DIRECTORY_ENTRY_SIZE;
+            let stream_type = u32_at(&directory, entry)?;
+            let size = u32_at(&directory, entry + 4)? as usize;
+            let rva = u32_at(&directory, entry + 8)? as u64;
+
+            match stream_type {
+                MODULE_LIST_STREAM => {
+                    let stream = read_at(&mut file, rva, size)?;
+
+                    for j in 0..u32_at(&stream, 0)? as usize {
+                        let module = 4 + j * MODULE_SIZE;
+                        let name_rva = u32_at(&stream, module + 20)? as u64;
+                        let path = read_string_at(&mut file, name_rva)?;
+
+                        modules.push(Module {
+                            name: file_name(&path),
+                            path,
+                            base_address: u64_at(&stream, module)? as usize,
+                            size: u32_at(&stream, module + 8)? as usize,
+                            entry_point: 0,
+                        });
+                    }
+                }
+                THREAD_LIST_STREAM => {
+                    let stream = read_at(&mut file, rva, size)?;
+
+                    for j in 0..u32_at(&stream, 0)? as usize {
+                        let thread = 4 + j * THREAD_SIZE;
+
+                        threads.push(MinidumpThread {
+                            id: u32_at(&stream, thread)?,
+                            teb: u64_at(&stream, thread + 16)?,
+                            stack_address: u64_at(&stream, thread + 24)?,
+                        });
+                    }
+                }
+                MEMORY_LIST_STREAM => {
+                    let stream = read_at(&mut file, rva, size)?;
+
+                    for j in 0..u32_at(&stream, 0)? as usize {
+                        let descriptor = 4 + j * MEMORY_DESCRIPTOR_SIZE;
+
+                        blocks.push(FileBlock {
+                            address: u64_at(&stream, descriptor)? as usize,
+                            size: u32_at(&stream, descriptor + 8)? as usize,
+                            file_offset: u32_at(&stream, descriptor + 12)? as u64,
+                        });
+                    }
+                }
+                MEMORY_64_LIST_STREAM => {
+                    let stream = read_at(&mut file, rva, size)?;
+                    let mut file_offset = u64_at(&stream, 8)?;
+
+                    for j in 0..u64_at(&stream, 0)? as usize {
+                        let descriptor = 16 + j * MEMORY_DESCRIPTOR_SIZE;
+                        let size = u64_at(&stream, descriptor + 8)?;
+
+                        blocks.push(FileBlock {
+                            address: u64_at(&stream, descriptor)? as usize,
+                            size: size as usize,
+                            file_offset,
+                        });
+
+                        file_offset = match file_offset.checked_add(size) {
+                            Some(file_offset) => file_offset,
+                            None => {
+                                return Err(Error::new(
+                                    ErrorKind::InvalidData,
+                                    "memory ranges overflow the file",
+                                ))
+                            }
+                        };
+                    }
+                }
+                MEMORY_INFO_LIST_STREAM => {
+                    let stream = read_at(&mut file, rva, size)?;
+                    let header_size = u32_at(&stream, 0)? as usize;
+                    let entry_size = u32_at(&stream, 4)? as usize;
+                    let nb_infos = u64_at(&stream, 8)?;
+
+                    // The count is checked against the stream before anything is allocated
+                    let infos_size = (entry_size as u64)
+                        .checked_mul(nb_infos)
+                        .and_then(|infos_size| infos_size.checked_add(header_size as u64));
+
+                    match infos_size {
+                        Some(infos_size)
+                            if header_size >= MEMORY_INFO_LIST_HEADER_SIZE
+                                && entry_size >= MEMORY_INFO_SIZE
+                                && infos_size <= stream.len() as u64 => {}
+                        _ => {
+                            return Err(Error::new(
+                                ErrorKind::InvalidData,
+                                "invalid memory info list",
+                            ))
+                        }
+                    }
+
+                    for j in 0..nb_infos as usize {
+                        let info = header_size + j * entry_size;
+
+                        if u32_at(&stream, info + 32)? == MEM_FREE {
+                            continue;
+                        }
+
+                        regions.push(MemoryRegion {
+                            base_address: u64_at(&stream, info)? as usize,
+                            size: u64_at(&stream, info + 24)? as usize,
+                            protection: from_page_protection(u32_at(&stream, info + 36)?),
+                            state: match u32_at(&stream, info + 32)? {
+                                MEM_COMMIT => RegionState::Committed,
+                                _ => RegionState::Reserved,
+                            },
+                            kind: match u32_at(&stream, info + 40)? {
+                                MEM_IMAGE => RegionType::Image,
+                                MEM_MAPPED => RegionType::Mapped,
+                                MEM_PRIVATE => RegionType::Private,
+                                _ => RegionType::Unknown,
+                            },
+                            path: None,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Without memory info list, the dumped ranges are the only known regions
+        if regions.is_empty() {
+            regions = blocks
+                .iter()
+                .map(|block| MemoryRegion {
+                    base_address: block.address,
+                    size: block.size,
+                    protection: Protection::READ,
+                    state: RegionState::Committed,
+                    kind: RegionType::Unknown,
+                    path: None,
+                })
+                .collect();
+        }
+
+        regions.sort_by_key(|region| region.base_address);
+
+        for region in &mut regions {
+            if let Some(module) = modules.iter().find(|m| m.contains(region.base_address)) {
+                region.kind = RegionType::Image;
+                region.path = Some(module.path.clone());
+            }
+        }
+
+        let mut minidump = Minidump {
+            modules,
+            threads,
+            regions,
+            memory: FileMemory::new(file, blocks)?,
+        };
+
+        // The entry points are not stored in a minidump, read them in the dumped headers
+        for i in 0..minidump.modules.len() {
+            if let Some(header) = read_pe_header(&minidump, minidump.modules[i].base_address) {
+                minidump.modules[i].entry_point = header.entry_point;
+            }
+        }
+
+        Ok(minidump)
+    }
+}
+
+impl MemoryBackend for Minidump {
+    fn read_bytes(&self, address: usize, buffer: &mut [u8]) -> Result<usize, Error> {
+        self.memory.read(address, buffer)
+    }
+
+    fn write_bytes(&self, address: usize, _buffer: &[u8]) -> Result<usize, Error> {
+        Err(Error::new(
+            ErrorKind::PermissionDenied,
+            format!("cannot write at {:#x}, a minidump is read-only", address),
+        ))
+    }
+
+    fn allocate(&self, _size: usize, _protection: Protection) -> Result<usize, Error> {
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "cannot allocate memory in a minidump",
+        ))
+    }
+
+    fn query_region(&self, address: usize) -> Result<MemoryRegion, Error> {
+        Ok(file_format::find_region(&self.regions, address))
+    }
+
+    fn regions(&self) -> Result<Vec<MemoryRegion>, Error> {
+        Ok(self.regions.clone())
+    }
+
+    fn main_module_base(&self) -> usize {
+        self.modules
+            .first()
+            .map(|module| module.base_address)
+            .unwrap_or(0)
+    }
+
+    fn modules(&self) -> Result<Vec<Module>, Error> {
+        Ok(self.modules.clone())
+    }
+}
+
+/// Write a minidump of the specified process with its memory, modules and threads.
+///
+/// # Arguments
+/// process - The process to dump
+/// file - The path of the minidump file
+///
+/// # Returns
+/// If the function succeeds, the return value is nothing.
+pub fn write_process(process: &Process, file: impl AsRef<Path>) -> Result<(), Error> {
+    let threads: Vec<MinidumpThread> = process::threads(process)?
+        .into_iter()
+        .map(|id| MinidumpThread {
+            id,
+            ..MinidumpThread::default()
+        })
+        .collect();
+
+    write(process, &threads, file)
+}
+
+/// Write a minidump with the system info, module list, thread list, memory info list and 64 bits
+/// memory list streams (every readable region is dumped, the thread contexts are not captured).
+///
+/// # Arguments
+/// process - The process to dump
+/// threads - The threads of the process
+/// file - The path of the minidump file
+///
+/// # Returns
+/// If the function succeeds, the return value is nothing.
+pub fn write(
+    process: &(impl MemoryBackend + ?Sized),
+    threads: &[MinidumpThread],
+    file: impl AsRef<Path>,
+) -> Result<(), Error> {
+    let modules = process.modules()?;
+    let regions = process.regions()?;
+
+    let stream_types = [
+        SYSTEM_INFO_STREAM,
+        MODULE_LIST_STREAM,
+        THREAD_LIST_STREAM,
+        MEMORY_INFO_LIST_STREAM,
+        MEMORY_64_LIST_STREAM,
+    ];
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as u32)
+        .unwrap_or(0);
+
+    let mut out = Vec::new();
+
+    put_u32(&mut out, MINIDUMP_SIGNATURE);
+    put_u32(&mut out, MINIDUMP_VERSION);
+    put_u32(&mut out, stream_types.len() as u32);
+    put_u32(&mut out, HEADER_SIZE as u32);
+    put_u32(&mut out, 0);
+    put_u32(&mut out, timestamp);
+    put_u64(&mut out, 0);
+
+    // The directory is filled once the streams are written
+    out.resize(HEADER_SIZE + stream_types.len() * DIRECTORY_ENTRY_SIZE, 0);
+
+    let mut locations = Vec::new();
+
+    // System info, followed by its empty service pack string
+    let start = out.len();
+    let architecture: u16 = match std::env::consts::ARCH {
+        "x86" => 0,
+        "arm" => 5,
+        "x86_64" => 9,
+        "aarch64" => 12,
+        _ => 0xFFFF,
+    };
+    let nb_processors = std::thread::available_parallelism()
+        .map(|n| n.get().min(255) as u8)
+        .unwrap_or(1);
+
+    put_u16(&mut out, architecture);
+    put_u16(&mut out, 0);
+    put_u16(&mut out, 0);
+    out.push(nb_processors);
+    out.push(1);
+    put_u32(&mut out, 0);
+    put_u32(&mut out, 0);
+    put_u32(&mut out, 0);
+    put_u32(&mut out, 2);
+    put_u32(&mut out, (start + SYSTEM_INFO_SIZE) as u32);
+    out.resize(start + SYSTEM_INFO_SIZE, 0);
+    locations.push((start, SYSTEM_INFO_SIZE));
+    put_string(&mut out, "");
+
+    // Module list, followed by the names of the modules
+    let start = out.len();
+    let size = 4 + modules.len() * MODULE_SIZE;
+
+    put_u32(&mut out, modules.len() as u32);
+    out.resize(start + size, 0);
+    locations.push((start, size));
+
+    for (i, module) in modules.iter().enumerate() {
+        let name_rva = out.len() as u32;
+        let header = read_pe_header(process, module.base_address).unwrap_or_default();
+
+        put_string(&mut out, &module.path);
+
+        let mut entry = Vec::new();
+
+        put_u64(&mut entry, module.base_address as u64);
+        put_u32(&mut entry, module.size as u32);
+        put_u32(&mut entry, header.checksum);
+        put_u32(&mut entry, header.timestamp);
+        put_u32(&mut entry, name_rva);
+
+        let offset = start + 4 + i * MODULE_SIZE;
+
+        out[offset..offset + entry.len()].copy_from_slice(&entry);
+    }
+
+    // Thread list
+    let start = out.len();
+
+    put_u32(&mut out, threads.len() as u32);
+
+    for thread in threads {
+        put_u32(&mut out, thread.id);
+        put_u32(&mut out, 0);
+        put_u32(&mut out, 0);
+        put_u32(&mut out, 0);
+        put_u64(&mut out, thread.teb);
+        put_u64(&mut out, thread.stack_address);
+        put_u64(&mut out, 0);
+        put_u64(&mut out, 0);
+    }
+
+    locations.push((start, out.len() - start));
+
+    // Memory info list
+    let start = out.len();
+
+    put_u32(&mut out, MEMORY_INFO_LIST_HEADER_SIZE as u32);
+    put_u32(&mut out, MEMORY_INFO_SIZE as u32);
+    put_u64(&mut out, regions.len() as u64);
+
+    for region in &regions {
+        let protection = to_page_protection(region.protection);
+
+        put_u64(&mut out, region.base_address as u64);
+        put_u64(&mut out, region.base_address as u64);
+        put_u32(&mut out, protection);
+        put_u32(&mut out, 0);
+        put_u64(&mut out, region.size as u64);
+        put_u32(
+            &mut out,
+            match region.state {
+                RegionState::Committed => MEM_COMMIT,
+                RegionState::Reserved => MEM_RESERVE,
+                RegionState::Free => MEM_FREE,
+            },
+        );
+        put_u32(&mut out, protection);
+        put_u32(
+            &mut out,
+            match region.kind {
+                RegionType::Image => MEM_IMAGE,
+                RegionType::Mapped => MEM_MAPPED,
+                RegionType::Private | RegionType::Unknown => MEM_PRIVATE,
+            },
+        );
+        put_u32(&mut out, 0);
+    }
+
+    locations.push((start, out.len() - start));
+
+    // 64 bits memory list: room is reserved for one descriptor per read window, the descriptors
+    // are written once the readable windows are known
+    let memory_list_start = out.len();
+    let max_descriptors: usize = regions
+        .iter()
+        .filter(|region| region.is_readable())
+        .map(|region| region.size.div_ceil(MINIDUMP_WINDOW_SIZE))
+        .sum();
+    let base_rva = (memory_list_start + 16 + max_descriptors * MEMORY_DESCRIPTOR_SIZE) as u64;
+
+    if base_rva > u32::MAX as u64 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "too many regions for a minidump",
+        ));
+    }
+
+    out.resize(base_rva as usize, 0);
+
+    let mut writer = BufWriter::new(File::create(file)?);
+
+    writer.write_all(&out)?;
+
+    let mut descriptors: Vec<(u64, u64)> = Vec::new();
+    let mut buffer = Vec::new();
+
+    for region in regions.iter().filter(|region| region.is_readable()) {
+        let mut window_address = region.base_address;
+
+        while window_address < region.end_address() {
+            let window_end = region
+                .end_address()
+                .min(window_address.saturating_add(MINIDUMP_WINDOW_SIZE));
+
+            buffer.resize(window_end - window_address, 0);
+
+            if process.read_bytes(window_address, &mut buffer).is_ok() {
+                writer.write_all(&buffer)?;
+
+                // The data is contiguous in the file, merge the contiguous ranges
+                match descriptors.last_mut() {
+                    Some((address, size)) if *address + *size == window_address as u64 => {
+                        *size += buffer.len() as u64;
+                    }
+                    _ => descriptors.push((window_address as u64, buffer.len() as u64)),
+                }
+            }
+
+            window_address = window_end;
+        }
+    }
+
+    let mut memory_list = Vec::new();
+
+    put_u64(&mut memory_list, descriptors.len() as u64);
+    put_u64(&mut memory_list, base_rva);
+
+    for (address, size) in &descriptors {
+        put_u64(&mut memory_list, *address);
+        put_u64(&mut memory_list, *size);
+    }
+
+    locations.push((memory_list_start, memory_list.len()));
+
+    writer.seek(SeekFrom::Start(memory_list_start as u64))?;
+    writer.write_all(&memory_list)?;
+
+    let mut directory = Vec::new();
+
+    for (stream_type, (rva, size)) in stream_types.iter().zip(locations) {
+        put_u32(&mut directory, *stream_type);
+        put_u32(&mut directory, size as u32);
+        put_u32(&mut directory, rva as u32);
+    }
+
+    writer.seek(SeekFrom::Start(HEADER_SIZE as u64))?;
+    writer.write_all(&directory)?;
+    writer.flush()
+}
+
+/// Represent the fields of a PE header stored in a minidump.
+#[derive(Default)]
+struct PeHeader {
+    /// The link time of the module.
+    timestamp: u32,
+
+    /// The checksum of the module.
+    checksum: u32,
+
+    /// The absolute address of the entry point (0 if none).
+    entry_point: usize,
+}
+
+/// Read the PE header of the module loaded at the specified address.
+///
+/// # Arguments
+/// process - The process that contains the module
+/// base_address - The base address of the module
+///
+/// # Returns
+/// The fields of the header, or None if there is no readable PE header at the address
+fn read_pe_header(
+    process: &(impl MemoryBackend + ?Sized),
+    base_address: usize,
+) -> Option<PeHeader> {
+    let mut dos_header = [0u8; 0x40];
+
+    process.read_bytes(base_address, &mut dos_header).ok()?;
+
+    if &dos_header[..2] != b"MZ" {
+        return None;
+    }
+
+    let nt_headers = base_address.checked_add(u32_at(&dos_header, 0x3C).ok()? as usize)?;
+    let mut header = [0u8; 0x5C];
+
+    process.read_bytes(nt_headers, &mut header).ok()?;
+
+    if &header[..4] != b"PE\0\0" {
+        return None;
+    }
+
+    let entry_point = u32_at(&header, 0x28).ok()? as usize;
+
+    Some(PeHeader {
+        timestamp: u32_at(&header, 0x08).ok()?,
+        checksum: u32_at(&header, 0x58).ok()?,
+        entry_point: match entry_point {
+            0 => 0,
+            rva => base_address.checked_add(rva)?,
+        },
+    })
+}
+
+/// Convert the specified protection to the Windows page protection.
+fn to_page_protection(protection: Protection) -> u32 {
+    match (
+        protection.is_readable(),
+        protection.is_writable(),
+        protection.is_executable(),
+    ) {
+        (_, true, true) => PAGE_EXECUTE_READWRITE,
+        (true, false, true) => PAGE_EXECUTE_READ,
+        (false, false, true) => PAGE_EXECUTE,
+        (_, true, false) => PAGE_READWRITE,
+        (true, false, false) => PAGE_READONLY,
+        (false, false, false) => PAGE_NOACCESS,
+    }
+}
+
+/// Convert the specified Windows page protection (modifiers are ignored).
+fn from_page_protection(protection: u32) -> Protection {
+    match protection & 0xFF {
+        PAGE_READONLY => Protection::READ,
+        PAGE_READWRITE | PAGE_WRITECOPY => Protection::READ | Protection::WRITE,
+        PAGE_EXECUTE => Protection::EXECUTE,
+        PAGE_EXECUTE_READ => Protection::READ | Protection::EXECUTE,
+        PAGE_EXECUTE_READWRITE | PAGE_EXECUTE_WRITECOPY => {
+            Protection::READ | Protection::WRITE | Protection::EXECUTE
+        }
+        _ => Protection::NONE,
+    }
+}
+
+/// Get the file name of the specified path (Windows or Unix separators).
+fn file_name(path: &str) -> String {
+    path.rsplit(['\\', '/']).next().unwrap_or(path).to_string()
+}
+
+/// Read the UTF-16 string (MINIDUMP_STRING) at the specified position of the file.
+fn read_string_at(file: &mut File, rva: u64) -> Result<String, Error> {
+    let length = u32_at(&read_at(file, rva, 4)?, 0)? as usize;
+    let bytes = read_at(file, rva.saturating_add(4), length)?;
+
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+        .collect();
+
+    Ok(String::from_utf16_lossy(&units))
+}
+
+fn put_u16(out: &mut Vec<u8>, value: u16) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u64(out: &mut Vec<u8>, value: u64) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Append a UTF-16 string (MINIDUMP_STRING) with its terminator, padded to 4 bytes.
+fn put_string(out: &mut Vec<u8>, value: &str) {
+    let units: Vec<u16> = value.encode_utf16().collect();
+
+    put_u32(out, (units.len() * 2) as u32);
+
+    for unit in units.iter().chain([0u16].iter()) {
+        put_u16(out, *unit);
+    }
+
+    out.resize(out.len().div_ceil(4) * 4, 0);
+}
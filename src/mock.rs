@@ -0,0 +1,363 @@
+use std::collections::BTreeMap;
+use std::io::{Error, ErrorKind};
+use std::sync::RwLock;
+
+use crate::backend::{MemoryBackend, MemoryRegion, Module, Protection, RegionState, RegionType};
+
+/// The size of a page of the fake address space.
+pub const PAGE_SIZE: usize = 0x1000;
+
+/// The address where the allocations of the fake address space start.
+static ALLOCATION_BASE: usize = 0x7000_0000;
+
+/// Represent a page of the fake address space.
+struct MockPage {
+    /// The content of the page.
+    data: Box<[u8; PAGE_SIZE]>,
+
+    /// The access rights of the page.
+    protection: Protection,
+}
+
+/// Represent a fake process that owns a sparse, page-granular address space in memory.
+///
+/// It can be used anywhere a memory backend is accepted, to test the memory helpers without a
+/// live target process.
+pub struct MockProcess {
+    /// The base address of the main module.
+    pub main_module_base: usize,
+
+    /// The modules loaded in the fake address space.
+    pub modules: Vec<Module>,
+
+    /// The mapped pages, indexed by their base address.
+    pages: RwLock<BTreeMap<usize, MockPage>>,
+}
+
+impl Default for MockProcess {
+    fn default() -> Self {
+        MockProcess::new(0)
+    }
+}
+
+impl MockProcess {
+    /// Create a new fake process with an empty address space.
+    ///
+    /// # Arguments
+    /// main_module_base - The base address of the main module.
+    ///
+    /// # Returns
+    /// The created fake process
+    pub fn new(main_module_base: usize) -> MockProcess {
+        MockProcess {
+            main_module_base,
+            modules: Vec::new(),
+            pages: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    /// Map zeroed pages that cover the specified range (already mapped pages are only re-protected).
+    ///
+    /// # Arguments
+    /// self - The fake process
+    /// address - The first address of the range
+    /// size - The size of the range
+    /// protection - The access rights of the mapped pages
+    pub fn map(self: &MockProcess, address: usize, size: usize, protection: Protection) {
+        let mut pages = self.pages.write().unwrap();
+
+        for page_address in page_range(address, size) {
+            pages
+                .entry(page_address)
+                .or_insert_with(|| MockPage {
+                    data: Box::new([0; PAGE_SIZE]),
+                    protection,
+                })
+                .protection = protection;
+        }
+    }
+
+    /// Unmap the pages that cover the specified range.
+    ///
+    /// # Arguments
+    /// self - The fake process
+    /// address - The first address of the range
+    /// size - The size of the range
+    pub fn unmap(self: &MockProcess, address: usize, size: usize) {
+        let mut pages = self.pages.write().unwrap();
+
+        for page_address in page_range(address, size) {
+            pages.remove(&page_address);
+        }
+    }
+
+    /// Change the access rights of the mapped pages that cover the specified range.
+    ///
+    /// # Arguments
+    /// self - The fake process
+    /// address - The first address of the range
+    /// size - The size of the range
+    /// protection - The new access rights of the pages
+    pub fn protect(self: &MockProcess, address: usize, size: usize, protection: Protection) {
+        let mut pages = self.pages.write().unwrap();
+
+        for page_address in page_range(address, size) {
+            if let Some(page) = pages.get_mut(&page_address) {
+                page.protection = protection;
+            }
+        }
+    }
+
+    /// Copy the specified bytes in the fake address space, mapping the missing pages as read-write
+    /// and ignoring the access rights of the existing ones (useful to prepare a test).
+    ///
+    /// # Arguments
+    /// self - The fake process
+    /// address - The address to write to
+    /// bytes - The bytes to write
+    pub fn load(self: &MockProcess, address: usize, bytes: &[u8]) {
+        let mut pages = self.pages.write().unwrap();
+
+        for (i, byte) in bytes.iter().enumerate() {
+            let byte_address = address + i;
+
+            let page = pages
+                .entry(byte_address - byte_address % PAGE_SIZE)
+                .or_insert_with(|| MockPage {
+                    data: Box::new([0; PAGE_SIZE]),
+                    protection: Protection::READ | Protection::WRITE,
+                });
+
+            page.data[byte_address % PAGE_SIZE] = *byte;
+        }
+    }
+
+    /// Add a module to the fake process and map its pages as read-only if not already mapped.
+    ///
+    /// # Arguments
+    /// self - The fake process
+    /// module - The module to add
+    pub fn add_module(self: &mut MockProcess, module: Module) {
+        {
+            let mut pages = self.pages.write().unwrap();
+
+            for page_address in page_range(module.base_address, module.size) {
+                pages.entry(page_address).or_insert_with(|| MockPage {
+                    data: Box::new([0; PAGE_SIZE]),
+                    protection: Protection::READ,
+                });
+            }
+        }
+
+        self.modules.push(module);
+    }
+
+    /// Copy bytes between the fake address space and a buffer, checking the access rights.
+    ///
+    /// # Arguments
+    /// self - The fake process
+    /// address - The first address of the transfer
+    /// size - The number of bytes to transfer
+    /// required - The access rights required on every page
+    /// copy - The function that copy the bytes of a page (page data, offset in page, offset in buffer, length)
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the number of bytes transferred.
+    fn transfer<F>(
+        self: &MockProcess,
+        address: usize,
+        size: usize,
+        required: Protection,
+        mut copy: F,
+    ) -> Result<usize, Error>
+    where
+        F: FnMut(&mut [u8; PAGE_SIZE], usize, usize, usize),
+    {
+        if size == 0 {
+            return Ok(0);
+        }
+
+        if address.checked_add(size).is_none() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("range {:#x}+{:#x} overflows", address, size),
+            ));
+        }
+
+        let mut pages = self.pages.write().unwrap();
+
+        // Check the whole range before copying anything (a transfer is never partial)
+        for page_address in page_range(address, size) {
+            match pages.get(&page_address) {
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::NotFound,
+                        format!("address {:#x} is not mapped", page_address.max(address)),
+                    ))
+                }
+                Some(page) if !page.protection.contains(required) => {
+                    return Err(Error::new(
+                        ErrorKind::PermissionDenied,
+                        format!(
+                            "address {:#x} is not accessible ({})",
+                            page_address.max(address),
+                            page.protection
+                        ),
+                    ))
+                }
+                Some(_) => {}
+            }
+        }
+
+        let mut done = 0;
+
+        while done < size {
+            let current = address + done;
+            let page_offset = current % PAGE_SIZE;
+            let length = (PAGE_SIZE - page_offset).min(size - done);
+
+            let page = pages.get_mut(&(current - page_offset)).unwrap();
+
+            copy(&mut page.data, page_offset, done, length);
+
+            done += length;
+        }
+
+        Ok(size)
+    }
+}
+
+impl MemoryBackend for MockProcess {
+    fn read_bytes(&self, address: usize, buffer: &mut [u8]) -> Result<usize, Error> {
+        self.transfer(
+            address,
+            buffer.len(),
+            Protection::READ,
+            |data, page_offset, offset, length| {
+                buffer[offset..offset + length]
+                    .copy_from_slice(&data[page_offset..page_offset + length]);
+            },
+        )
+    }
+
+    fn write_bytes(&self, address: usize, buffer: &[u8]) -> Result<usize, Error> {
+        self.transfer(
+            address,
+            buffer.len(),
+            Protection::WRITE,
+            |data, page_offset, offset, length| {
+                data[page_offset..page_offset + length]
+                    .copy_from_slice(&buffer[offset..offset + length]);
+            },
+        )
+    }
+
+    fn allocate(&self, size: usize, protection: Protection) -> Result<usize, Error> {
+        let nb_pages = size.div_ceil(PAGE_SIZE).max(1);
+
+        let address = {
+            let pages = self.pages.read().unwrap();
+
+            // Allocate after the last page mapped above the allocation base
+            pages
+                .range(ALLOCATION_BASE..)
+                .next_back()
+                .map(|(page_address, _)| page_address + PAGE_SIZE)
+                .unwrap_or(ALLOCATION_BASE)
+        };
+
+        self.map(address, nb_pages * PAGE_SIZE, protection);
+
+        Ok(address)
+    }
+
+    fn query_region(&self, address: usize) -> Result<MemoryRegion, Error> {
+        let pages = self.pages.read().unwrap();
+
+        let page_address = address - address % PAGE_SIZE;
+
+        let module = self.modules.iter().find(|m| m.contains(address));
+
+        let kind = if module.is_some() {
+            RegionType::Image
+        } else {
+            RegionType::Private
+        };
+
+        let protection = match pages.get(&page_address) {
+            Some(page) => page.protection,
+            None => {
+                // The free region spans between the surrounding mapped pages
+                let start = pages
+                    .range(..page_address)
+                    .next_back()
+                    .map(|(previous, _)| previous + PAGE_SIZE)
+                    .unwrap_or(0);
+
+                let end = pages
+                    .range(page_address..)
+                    .next()
+                    .map(|(next, _)| *next)
+                    .unwrap_or(usize::MAX);
+
+                return Ok(MemoryRegion {
+                    base_address: start,
+                    size: end - start,
+                    protection: Protection::NONE,
+                    state: RegionState::Free,
+                    kind: RegionType::Unknown,
+                    path: None,
+                });
+            }
+        };
+
+        let same_region = |other: usize| {
+            pages.get(&other).map(|page| page.protection) == Some(protection)
+                && self.modules.iter().any(|m| m.contains(other)) == (kind == RegionType::Image)
+        };
+
+        let mut start = page_address;
+
+        while start >= PAGE_SIZE && same_region(start - PAGE_SIZE) {
+            start -= PAGE_SIZE;
+        }
+
+        let mut end = page_address + PAGE_SIZE;
+
+        while end <= usize::MAX - PAGE_SIZE && same_region(end) {
+            end += PAGE_SIZE;
+        }
+
+        Ok(MemoryRegion {
+            base_address: start,
+            size: end - start,
+            protection,
+            state: RegionState::Committed,
+            kind,
+            path: module.map(|m| m.path.clone()),
+        })
+    }
+
+    fn main_module_base(&self) -> usize {
+        self.main_module_base
+    }
+
+    fn modules(&self) -> Result<Vec<Module>, Error> {
+        Ok(self.modules.clone())
+    }
+}
+
+/// Get the base address of every page that covers the specified range.
+///
+/// # Arguments
+/// address - The first address of the range
+/// size - The size of the range
+///
+/// # Returns
+/// An iterator over the base address of the pages.
+fn page_range(address: usize, size: usize) -> impl Iterator<Item = usize> {
+    let first = address - address % PAGE_SIZE;
+    let last = address.saturating_add(size.max(1) - 1);
+
+    (first..=last).step_by(PAGE_SIZE)
+}
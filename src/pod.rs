@@ -0,0 +1,124 @@
+use std::io::{Error, ErrorKind};
+
+pub use wapi_derive::Pod;
+
+/// Represent a "plain old data" type: a type that can be read from or written to the memory of a
+/// process as raw bytes.
+///
+/// Derive it for your own structs with `#[derive(Clone, Copy, Pod)]` and `#[repr(C)]`, the
+/// derive checks that every field is Pod and that the struct has no padding.
+///
+/// # Safety
+/// Every bit pattern of the size of the type must be a valid value of the type, and the type must
+/// not have padding bytes. The types that have invalid bit patterns (bool, char, enums,
+/// references...) must go through `Validated` instead.
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! impl_pod {
+    ($($ty:ty),*) => {
+        $(unsafe impl Pod for $ty {})*
+    };
+}
+
+impl_pod!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+/// Fail to compile if the specified type is not Pod (used by the derive).
+#[doc(hidden)]
+pub const fn assert_pod<T: Pod>() {}
+
+/// Get a value of the specified type made of zero bytes.
+pub fn zeroed<T: Pod>() -> T {
+    unsafe { std::mem::zeroed() }
+}
+
+/// Get the bytes of the specified value.
+pub fn bytes_of<T: Pod>(value: &T) -> &[u8] {
+    unsafe { std::slice::from_raw_parts(value as *const T as *const u8, std::mem::size_of::<T>()) }
+}
+
+/// Get the bytes of the specified value, to modify them.
+pub fn bytes_of_mut<T: Pod>(value: &mut T) -> &mut [u8] {
+    unsafe { std::slice::from_raw_parts_mut(value as *mut T as *mut u8, std::mem::size_of::<T>()) }
+}
+
+/// Build a value from its bytes.
+///
+/// # Arguments
+/// bytes - The bytes of the value (any alignment)
+///
+/// # Returns
+/// If the function succeeds, the return value is the value. It fails if the number of bytes is
+/// not the size of the type.
+pub fn from_bytes<T: Pod>(bytes: &[u8]) -> Result<T, Error> {
+    let mut value = zeroed::<T>();
+    let value_bytes = bytes_of_mut(&mut value);
+
+    if bytes.len() != value_bytes.len() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("expected {} bytes, got {}", value_bytes.len(), bytes.len()),
+        ));
+    }
+
+    value_bytes.copy_from_slice(bytes);
+
+    Ok(value)
+}
+
+/// Represent a type that is stored in memory as a Pod type but that does not accept every bit
+/// pattern (bool, char, enums...), so it is converted and validated after being read.
+pub trait Validated: Sized {
+    /// The type of the bits in memory.
+    type Raw: Pod;
+
+    /// Convert the bits read from memory.
+    ///
+    /// # Arguments
+    /// raw - The bits read from memory
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the value. It fails with InvalidData if the
+    /// bits are not a valid value.
+    fn from_raw(raw: Self::Raw) -> Result<Self, Error>;
+
+    /// Convert the value into the bits to write in memory.
+    fn to_raw(&self) -> Self::Raw;
+}
+
+impl Validated for bool {
+    type Raw = u8;
+
+    fn from_raw(raw: u8) -> Result<bool, Error> {
+        match raw {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("{:#x} is not a valid bool", raw),
+            )),
+        }
+    }
+
+    fn to_raw(&self) -> u8 {
+        *self as u8
+    }
+}
+
+impl Validated for char {
+    type Raw = u32;
+
+    fn from_raw(raw: u32) -> Result<char, Error> {
+        char::from_u32(raw).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("{:#x} is not a valid char", raw),
+            )
+        })
+    }
+
+    fn to_raw(&self) -> u32 {
+        *self as u32
+    }
+}
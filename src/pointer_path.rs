@@ -0,0 +1,396 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::memory::{MultiLevelPointer, PointerBase};
+
+/// The name used in pointer paths for the main module of the process.
+pub static MAIN_MODULE_NAME: &str = "<main>";
+
+/// Represent an error that occurred while parsing a pointer path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParsePointerError {
+    /// The position (in characters, starting at 0) of the offending token.
+    pub column: usize,
+
+    /// The offending token (empty at the end of the input).
+    pub token: String,
+
+    /// What was expected instead of the offending token.
+    pub expected: &'static str,
+}
+
+impl fmt::Display for ParsePointerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.token.is_empty() {
+            write!(
+                f,
+                "unexpected end of pointer path at column {}, expected {}",
+                self.column, self.expected
+            )
+        } else {
+            write!(
+                f,
+                "unexpected \"{}\" at column {}, expected {}",
+                self.token, self.column, self.expected
+            )
+        }
+    }
+}
+
+impl std::error::Error for ParsePointerError {}
+
+/// Represent a token of a pointer path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    OpenBracket,
+    CloseBracket,
+    Plus,
+    Minus,
+    Arrow,
+    Quoted(String),
+    Word(String),
+}
+
+impl Token {
+    /// Get the text of the token as written in a pointer path.
+    fn text(self: &Token) -> String {
+        match self {
+            Token::OpenBracket => String::from("["),
+            Token::CloseBracket => String::from("]"),
+            Token::Plus => String::from("+"),
+            Token::Minus => String::from("-"),
+            Token::Arrow => String::from("->"),
+            Token::Quoted(text) => format!("\"{}\"", text),
+            Token::Word(text) => text.clone(),
+        }
+    }
+}
+
+/// Represent the state of the parsing of a pointer path.
+struct Parser {
+    /// The tokens with their column.
+    tokens: Vec<(usize, Token)>,
+
+    /// The index of the next token to read.
+    position: usize,
+
+    /// The length of the input (column of the end of input).
+    end_column: usize,
+}
+
+impl Parser {
+    /// Split the specified pointer path into tokens.
+    ///
+    /// # Arguments
+    /// input - The pointer path
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the parser ready to read the first token.
+    fn new(input: &str) -> Result<Parser, ParsePointerError> {
+        let chars: Vec<char> = input.chars().collect();
+        let mut tokens = Vec::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            let column = i;
+
+            let token = match chars[i] {
+                c if c.is_whitespace() => {
+                    i += 1;
+                    continue;
+                }
+                '[' => Token::OpenBracket,
+                ']' => Token::CloseBracket,
+                '+' => Token::Plus,
+                '-' if chars.get(i + 1) == Some(&'>') => {
+                    i += 1;
+                    Token::Arrow
+                }
+                '-' => Token::Minus,
+                '"' => {
+                    let end = chars[i + 1..]
+                        .iter()
+                        .position(|c| *c == '"')
+                        .ok_or_else(|| ParsePointerError {
+                            column,
+                            token: chars[i..].iter().collect(),
+                            expected: "a closing quote",
+                        })?;
+
+                    let text = chars[i + 1..i + 1 + end].iter().collect();
+
+                    i += end + 1;
+                    Token::Quoted(text)
+                }
+                _ => {
+                    let start = i;
+
+                    while i + 1 < chars.len() && is_word_char(&chars, i + 1) {
+                        i += 1;
+                    }
+
+                    Token::Word(chars[start..=i].iter().collect())
+                }
+            };
+
+            tokens.push((column, token));
+            i += 1;
+        }
+
+        Ok(Parser {
+            tokens,
+            position: 0,
+            end_column: chars.len(),
+        })
+    }
+
+    /// Get the next token without consuming it.
+    fn peek(self: &Parser) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(_, token)| token)
+    }
+
+    /// Consume the next token if it is equal to the specified one.
+    fn accept(self: &mut Parser, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consume the next token that must be equal to the specified one.
+    fn expect(
+        self: &mut Parser,
+        token: &Token,
+        expected: &'static str,
+    ) -> Result<(), ParsePointerError> {
+        if self.accept(token) {
+            Ok(())
+        } else {
+            Err(self.error(expected))
+        }
+    }
+
+    /// Create an error that points at the next token.
+    fn error(self: &Parser, expected: &'static str) -> ParsePointerError {
+        match self.tokens.get(self.position) {
+            Some((column, token)) => ParsePointerError {
+                column: *column,
+                token: token.text(),
+                expected,
+            },
+            None => ParsePointerError {
+                column: self.end_column,
+                token: String::new(),
+                expected,
+            },
+        }
+    }
+
+    /// Parse a hexadecimal number (with or without "0x" prefix).
+    fn number(self: &mut Parser, expected: &'static str) -> Result<usize, ParsePointerError> {
+        match self.peek() {
+            Some(Token::Word(word)) => match parse_hex(word) {
+                Some(value) => {
+                    self.position += 1;
+                    Ok(value)
+                }
+                None => Err(self.error(expected)),
+            },
+            _ => Err(self.error(expected)),
+        }
+    }
+
+    /// Parse a signed offset ("+50", "-10", or "50" when the sign is optional).
+    fn offset(self: &mut Parser, sign_required: bool) -> Result<usize, ParsePointerError> {
+        if self.accept(&Token::Minus) {
+            Ok(self.number("a hexadecimal offset")?.wrapping_neg())
+        } else if self.accept(&Token::Plus) || !sign_required {
+            self.number("a hexadecimal offset")
+        } else {
+            Err(self.error("\"+\" or \"-\""))
+        }
+    }
+
+    /// Parse the base of the pointer ("game.exe"+1234, game.exe+1234, <main>+1234 or 7FF61234).
+    fn base(self: &mut Parser) -> Result<(PointerBase, usize), ParsePointerError> {
+        let module = match self.peek() {
+            Some(Token::Quoted(name)) => name.clone(),
+            Some(Token::Word(word)) if parse_hex(word).is_some() => {
+                return Ok((PointerBase::Absolute, self.number("an address")?));
+            }
+            Some(Token::Word(word)) => word.clone(),
+            _ => return Err(self.error("a module name or an address")),
+        };
+
+        self.position += 1;
+
+        let base = if module == MAIN_MODULE_NAME {
+            PointerBase::MainModule
+        } else {
+            PointerBase::Module(module)
+        };
+
+        match self.peek() {
+            Some(Token::Plus) | Some(Token::Minus) => Ok((base, self.offset(true)?)),
+            _ => Ok((base, 0)),
+        }
+    }
+
+    /// Parse a pointer in Cheat Engine bracket notation ([[[game.exe+91AD2C0]+50]+10]+20).
+    fn bracket_pointer(self: &mut Parser) -> Result<MultiLevelPointer, ParsePointerError> {
+        let mut depth = 0;
+
+        while self.accept(&Token::OpenBracket) {
+            depth += 1;
+        }
+
+        let (base, base_address) = self.base()?;
+
+        self.expect(&Token::CloseBracket, "\"]\"")?;
+
+        let mut offsets = Vec::new();
+
+        for _ in 1..depth {
+            offsets.push(self.offset(true)?);
+            self.expect(&Token::CloseBracket, "\"]\"")?;
+        }
+
+        offsets.push(if self.peek().is_some() {
+            self.offset(true)?
+        } else {
+            0
+        });
+
+        if self.peek().is_some() {
+            return Err(self.error("the end of the pointer path"));
+        }
+
+        Ok(MultiLevelPointer {
+            base,
+            base_address,
+            offsets,
+        })
+    }
+
+    /// Parse a pointer in arrow notation ("game.exe"+091AD2C0 -> 50 -> 10 -> 20).
+    fn arrow_pointer(self: &mut Parser) -> Result<MultiLevelPointer, ParsePointerError> {
+        let (base, base_address) = self.base()?;
+
+        let mut offsets = Vec::new();
+
+        while self.peek().is_some() {
+            self.expect(&Token::Arrow, "\"->\"")?;
+            offsets.push(self.offset(false)?);
+        }
+
+        Ok(MultiLevelPointer {
+            base,
+            base_address,
+            offsets,
+        })
+    }
+}
+
+/// Check if the character at the specified position continues a word (module name or number).
+///
+/// A word ends at a sign, so a module name that contains one must be quoted.
+fn is_word_char(chars: &[char], i: usize) -> bool {
+    match chars[i] {
+        c if c.is_whitespace() => false,
+        '[' | ']' | '+' | '-' | '"' => false,
+        _ => true,
+    }
+}
+
+/// Parse a hexadecimal number with or without "0x" prefix.
+fn parse_hex(word: &str) -> Option<usize> {
+    let digits = word
+        .strip_prefix("0x")
+        .or_else(|| word.strip_prefix("0X"))
+        .unwrap_or(word);
+
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    usize::from_str_radix(digits, 16).ok()
+}
+
+/// Format an offset as a signed hexadecimal number (offsets above isize::MAX are negative).
+fn format_offset(offset: usize) -> String {
+    if (offset as isize) < 0 {
+        format!("-{:X}", offset.wrapping_neg())
+    } else {
+        format!("+{:X}", offset)
+    }
+}
+
+impl FromStr for MultiLevelPointer {
+    type Err = ParsePointerError;
+
+    /// Parse a pointer path in Cheat Engine bracket notation (`[[[game.exe+91AD2C0]+50]+10]+20`)
+    /// or in arrow notation (`"game.exe"+091AD2C0 -> 50 -> 10 -> 20`).
+    ///
+    /// The numbers are hexadecimal, "<main>" is the main module and a base without module is an
+    /// absolute address. A module name that contains "+" or "-" must be quoted.
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(path)?;
+
+        if parser.peek() == Some(&Token::OpenBracket) {
+            parser.bracket_pointer()
+        } else {
+            parser.arrow_pointer()
+        }
+    }
+}
+
+impl fmt::Display for MultiLevelPointer {
+    /// Format the pointer in arrow notation, or in bracket notation with the alternate flag ({:#}).
+    ///
+    /// A signature base is only described, it cannot be parsed back.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let base = match &self.base {
+            PointerBase::Absolute => format!("{:X}", self.base_address),
+            PointerBase::MainModule => {
+                format!("{}{}", MAIN_MODULE_NAME, format_offset(self.base_address))
+            }
+            PointerBase::Module(name) => {
+                format!("\"{}\"{}", name, format_offset(self.base_address))
+            }
+            PointerBase::Signature(signature) => format!(
+                "<signature \"{}\" in {}>{}",
+                signature.pattern,
+                signature.module_name,
+                format_offset(self.base_address)
+            ),
+        };
+
+        if !f.alternate() {
+            write!(f, "{}", base)?;
+
+            for offset in &self.offsets {
+                write!(f, " -> {}", format_offset(*offset).trim_start_matches('+'))?;
+            }
+
+            return Ok(());
+        }
+
+        let depth = self.offsets.len().max(1);
+
+        write!(f, "{}{}]", "[".repeat(depth), base)?;
+
+        if let Some((last, offsets)) = self.offsets.split_last() {
+            for offset in offsets {
+                write!(f, "{}]", format_offset(*offset))?;
+            }
+
+            if *last != 0 {
+                write!(f, "{}", format_offset(*last))?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,475 @@
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Error, ErrorKind, Write};
+use std::path::Path;
+
+use crate::backend::{MemoryBackend, MemoryRegion, Module};
+use crate::file_format;
+use crate::memory::{MultiLevelPointer, PointerBase};
+
+/// The maximum number of bytes read at once while building a pointer map.
+static SCAN_WINDOW_SIZE: usize = 0x10_0000;
+
+/// The magic of the pointer map snapshot files.
+static SNAPSHOT_MAGIC: &[u8; 8] = b"WAPIPMAP";
+
+/// The version of the pointer map snapshot files.
+static SNAPSHOT_VERSION: u32 = 1;
+
+/// The size of a pointer of the scanned process (the same as the scanner).
+pub const POINTER_SIZE: usize = std::mem::size_of::<usize>();
+
+/// Represent the limits of a pointer scan.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PointerScanOptions {
+    /// The maximum number of offsets of a path.
+    pub max_depth: usize,
+
+    /// The maximum offset between a pointed address and the next address of the path.
+    pub max_offset: usize,
+
+    /// Stop the scan after the specified number of paths (None to find every path).
+    pub max_results: Option<usize>,
+}
+
+impl Default for PointerScanOptions {
+    fn default() -> Self {
+        PointerScanOptions {
+            max_depth: 5,
+            max_offset: 0x1000,
+            max_results: None,
+        }
+    }
+}
+
+/// Represent every aligned pointer of a process that points into one of its readable regions.
+pub struct PointerMap {
+    /// The base address of the main module.
+    pub main_module_base: usize,
+
+    /// The modules of the process (the static addresses are the ones inside a module).
+    pub modules: Vec<Module>,
+
+    /// The pointers as (address, value), sorted by address.
+    by_address: Vec<(usize, usize)>,
+
+    /// The pointers as (value, address), sorted by value.
+    by_value: Vec<(usize, usize)>,
+}
+
+impl PointerMap {
+    /// Create a pointer map from the specified pointers.
+    ///
+    /// # Arguments
+    /// main_module_base - The base address of the main module
+    /// modules - The modules of the process
+    /// pointers - The pointers as (address, value), in any order
+    ///
+    /// # Returns
+    /// The created pointer map
+    pub fn new(
+        main_module_base: usize,
+        modules: Vec<Module>,
+        mut pointers: Vec<(usize, usize)>,
+    ) -> PointerMap {
+        pointers.sort_unstable();
+        pointers.dedup_by_key(|(address, _)| *address);
+
+        let mut by_value: Vec<(usize, usize)> = pointers
+            .iter()
+            .map(|(address, value)| (*value, *address))
+            .collect();
+
+        by_value.sort_unstable();
+
+        PointerMap {
+            main_module_base,
+            modules,
+            by_address: pointers,
+            by_value,
+        }
+    }
+
+    /// Read every aligned pointer of the specified process that points into a readable region.
+    ///
+    /// The regions that cannot be read are skipped.
+    ///
+    /// # Arguments
+    /// process - The process to scan
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the pointer map of the process.
+    pub fn build(process: &(impl MemoryBackend + ?Sized)) -> Result<PointerMap, Error> {
+        let regions: Vec<MemoryRegion> = process
+            .regions()?
+            .into_iter()
+            .filter(|region| region.is_readable())
+            .collect();
+
+        let modules = process.modules()?;
+
+        let is_valid = |value: usize| {
+            let i = regions.partition_point(|region| region.end_address() <= value);
+
+            i < regions.len() && regions[i].contains(value)
+        };
+
+        let mut pointers = Vec::new();
+        let mut buffer = Vec::new();
+
+        for region in &regions {
+            let mut window_address = region.base_address;
+
+            while window_address < region.end_address() {
+                let window_end = region
+                    .end_address()
+                    .min(window_address.saturating_add(SCAN_WINDOW_SIZE));
+
+                buffer.resize(window_end - window_address, 0);
+
+                if process.read_bytes(window_address, &mut buffer).is_ok() {
+                    // The first aligned address of the window
+                    let mut offset = (POINTER_SIZE - window_address % POINTER_SIZE) % POINTER_SIZE;
+
+                    while offset + POINTER_SIZE <= buffer.len() {
+                        let value = usize::from_ne_bytes(
+                            buffer[offset..offset + POINTER_SIZE].try_into().unwrap(),
+                        );
+
+                        if is_valid(value) {
+                            pointers.push((window_address + offset, value));
+                        }
+
+                        offset += POINTER_SIZE;
+                    }
+                }
+
+                window_address = window_end;
+            }
+        }
+
+        Ok(PointerMap::new(
+            process.main_module_base(),
+            modules,
+            pointers,
+        ))
+    }
+
+    /// Get the number of pointers.
+    pub fn len(self: &PointerMap) -> usize {
+        self.by_address.len()
+    }
+
+    /// Check if the map contains no pointer.
+    pub fn is_empty(self: &PointerMap) -> bool {
+        self.by_address.is_empty()
+    }
+
+    /// Get the pointers as (address, value), sorted by address.
+    pub fn pointers(self: &PointerMap) -> &[(usize, usize)] {
+        &self.by_address
+    }
+
+    /// Save the pointer map in a snapshot file, so it can be scanned after the process exited.
+    ///
+    /// The file contains a header (magic "WAPIPMAP" and version), the base address of the main
+    /// module, the modules, then the pointers as (address, value). Every number is little-endian,
+    /// the addresses are stored on 64 bits and the strings are prefixed by their 32 bits length.
+    ///
+    /// # Arguments
+    /// self - The pointer map to save
+    /// file - The path of the snapshot file
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is nothing.
+    pub fn save(self: &PointerMap, file: impl AsRef<Path>) -> Result<(), Error> {
+        let mut writer = BufWriter::new(File::create(file)?);
+
+        file_format::write_header(&mut writer, SNAPSHOT_MAGIC, SNAPSHOT_VERSION)?;
+        file_format::write_usize(&mut writer, self.main_module_base)?;
+
+        file_format::write_usize(&mut writer, self.modules.len())?;
+
+        for module in &self.modules {
+            file_format::write_module(&mut writer, module)?;
+        }
+
+        file_format::write_usize(&mut writer, self.by_address.len())?;
+
+        for (address, value) in &self.by_address {
+            file_format::write_usize(&mut writer, *address)?;
+            file_format::write_usize(&mut writer, *value)?;
+        }
+
+        writer.flush()
+    }
+
+    /// Load a pointer map saved by save.
+    ///
+    /// # Arguments
+    /// file - The path of the snapshot file
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the loaded pointer map.
+    pub fn load(file: impl AsRef<Path>) -> Result<PointerMap, Error> {
+        let mut reader = BufReader::new(File::open(file)?);
+
+        file_format::read_header(&mut reader, SNAPSHOT_MAGIC, SNAPSHOT_VERSION)?;
+
+        let main_module_base = file_format::read_usize(&mut reader)?;
+
+        let nb_modules = file_format::read_usize(&mut reader)?;
+        let mut modules = Vec::new();
+
+        for _ in 0..nb_modules {
+            modules.push(file_format::read_module(&mut reader)?);
+        }
+
+        let nb_pointers = file_format::read_usize(&mut reader)?;
+        let mut pointers = Vec::new();
+
+        for _ in 0..nb_pointers {
+            let address = file_format::read_usize(&mut reader)?;
+            let value = file_format::read_usize(&mut reader)?;
+
+            pointers.push((address, value));
+        }
+
+        Ok(PointerMap::new(main_module_base, modules, pointers))
+    }
+
+    /// Get the value of the pointer at the specified address.
+    ///
+    /// # Arguments
+    /// self - The pointer map
+    /// address - The address of the pointer
+    ///
+    /// # Returns
+    /// The pointed address, or None if there is no valid pointer at the address
+    pub fn value_at(self: &PointerMap, address: usize) -> Option<usize> {
+        self.by_address
+            .binary_search_by_key(&address, |(address, _)| *address)
+            .ok()
+            .map(|i| self.by_address[i].1)
+    }
+
+    /// Get the module-relative location of the specified address if it is static.
+    ///
+    /// # Arguments
+    /// self - The pointer map
+    /// address - The address to locate
+    ///
+    /// # Returns
+    /// The module that contains the address with the offset from its base, or None if the address is not static
+    pub fn static_base(self: &PointerMap, address: usize) -> Option<(&Module, usize)> {
+        self.modules
+            .iter()
+            .find(|module| module.contains(address))
+            .map(|module| (module, address - module.base_address))
+    }
+
+    /// Compute the address pointed by the specified multi-level pointer using the pointers of the map.
+    ///
+    /// # Arguments
+    /// self - The pointer map
+    /// mlp - The multi-level pointer to follow (a signature base cannot be resolved offline)
+    ///
+    /// # Returns
+    /// The final address of the chain, or None if a hop is not a valid pointer
+    pub fn resolve(self: &PointerMap, mlp: &MultiLevelPointer) -> Option<usize> {
+        let base = match &mlp.base {
+            PointerBase::Absolute => 0,
+            PointerBase::MainModule => self.main_module_base,
+            PointerBase::Module(name) => {
+                self.modules
+                    .iter()
+                    .find(|module| module.name.eq_ignore_ascii_case(name))?
+                    .base_address
+            }
+            PointerBase::Signature(_) => return None,
+        };
+
+        let mut ptr = self.value_at(base.wrapping_add(mlp.base_address))?;
+
+        match mlp.offsets.split_last() {
+            None => Some(ptr),
+            Some((last, offsets)) => {
+                for offset in offsets {
+                    ptr = self.value_at(ptr.wrapping_add(*offset))?;
+                }
+
+                Some(ptr.wrapping_add(*last))
+            }
+        }
+    }
+
+    /// Find the static module-relative paths that reach the specified address.
+    ///
+    /// The shortest paths come first.
+    ///
+    /// # Arguments
+    /// self - The pointer map
+    /// target - The address to reach
+    /// options - The limits of the scan
+    ///
+    /// # Returns
+    /// The found paths
+    pub fn find_paths(
+        self: &PointerMap,
+        target: usize,
+        options: &PointerScanOptions,
+    ) -> Vec<MultiLevelPointer> {
+        let mut paths = Vec::new();
+
+        if options.max_depth > 0 {
+            self.find_paths_to(target, options, &mut Vec::new(), &mut paths);
+        }
+
+        paths.sort_by_key(|path| path.offsets.len());
+
+        paths
+    }
+
+    /// Walk back the pointers that point near the specified address (depth-first).
+    ///
+    /// # Arguments
+    /// self - The pointer map
+    /// target - The address to reach
+    /// options - The limits of the scan
+    /// offsets - The offsets from the target to the final address, in reverse order
+    /// paths - The found paths
+    fn find_paths_to(
+        self: &PointerMap,
+        target: usize,
+        options: &PointerScanOptions,
+        offsets: &mut Vec<usize>,
+        paths: &mut Vec<MultiLevelPointer>,
+    ) {
+        let lowest = target.saturating_sub(options.max_offset);
+        let first = self.by_value.partition_point(|(value, _)| *value < lowest);
+
+        for (value, address) in &self.by_value[first..] {
+            if *value > target {
+                break;
+            }
+
+            if options.max_results.is_some_and(|max| paths.len() >= max) {
+                return;
+            }
+
+            offsets.push(target - value);
+
+            if let Some((module, base_address)) = self.static_base(*address) {
+                paths.push(MultiLevelPointer {
+                    base: PointerBase::Module(module.name.clone()),
+                    base_address,
+                    offsets: offsets.iter().rev().copied().collect(),
+                });
+            }
+
+            if offsets.len() < options.max_depth {
+                self.find_paths_to(*address, options, offsets, paths);
+            }
+
+            offsets.pop();
+        }
+    }
+}
+
+/// Find the static module-relative paths that reach the specified address in the specified process.
+///
+/// # Arguments
+/// process - The process to scan
+/// target - The address to reach
+/// options - The limits of the scan
+///
+/// # Returns
+/// If the function succeeds, the return value is the found paths (the shortest first).
+pub fn scan(
+    process: &(impl MemoryBackend + ?Sized),
+    target: usize,
+    options: &PointerScanOptions,
+) -> Result<Vec<MultiLevelPointer>, Error> {
+    Ok(PointerMap::build(process)?.find_paths(target, options))
+}
+
+/// Keep only the paths that still reach the specified address (typically in a later run of the process).
+///
+/// # Arguments
+/// map - The pointer map of the process
+/// paths - The paths to check
+/// target - The address that the paths must reach
+///
+/// # Returns
+/// The paths that reach the target
+pub fn revalidate(
+    map: &PointerMap,
+    paths: &[MultiLevelPointer],
+    target: usize,
+) -> Vec<MultiLevelPointer> {
+    paths
+        .iter()
+        .filter(|path| map.resolve(path) == Some(target))
+        .cloned()
+        .collect()
+}
+
+/// Find the static paths that reach the target of every specified snapshot (typically taken in
+/// different runs of the process, where the target object has a different address).
+///
+/// # Arguments
+/// snapshots - The pointer maps with the address of the target in each of them
+/// options - The limits of the scan (applied to the first snapshot)
+///
+/// # Returns
+/// The paths that reach the target in every snapshot
+pub fn intersect(
+    snapshots: &[(&PointerMap, usize)],
+    options: &PointerScanOptions,
+) -> Vec<MultiLevelPointer> {
+    let ((first, target), others) = match snapshots.split_first() {
+        Some(split) => split,
+        None => return Vec::new(),
+    };
+
+    let mut paths = first.find_paths(*target, options);
+
+    for (map, target) in others {
+        paths = revalidate(map, &paths, *target);
+    }
+
+    paths
+}
+
+/// Save the specified paths in a text file, one path per line in arrow notation.
+///
+/// # Arguments
+/// file - The path of the file to write
+/// paths - The paths to save
+///
+/// # Returns
+/// If the function succeeds, the return value is nothing.
+pub fn save_paths(file: impl AsRef<Path>, paths: &[MultiLevelPointer]) -> Result<(), Error> {
+    let content: String = paths.iter().map(|path| format!("{}\n", path)).collect();
+
+    fs::write(file, content)
+}
+
+/// Load the paths saved by save_paths (empty lines are ignored).
+///
+/// # Arguments
+/// file - The path of the file to read
+///
+/// # Returns
+/// If the function succeeds, the return value is the loaded paths.
+pub fn load_paths(file: impl AsRef<Path>) -> Result<Vec<MultiLevelPointer>, Error> {
+    fs::read_to_string(file)?
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            line.parse().map_err(|error| {
+                Error::new(ErrorKind::InvalidData, format!("line {}: {}", i + 1, error))
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,293 @@
+use std::collections::HashMap;
+use std::io::Error;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, OnceLock, PoisonError};
+use std::thread::{self, JoinHandle, ThreadId};
+use std::time::{Duration, Instant};
+
+use crate::backend::MemoryBackend;
+
+/// Represent a kind of task run periodically on the memory of a process by a Poller.
+pub trait PollTask: Send + Sized + 'static {
+    /// The report of a task that fails.
+    type Error: Send + 'static;
+
+    /// Run the due tasks (all at once, so their reads can be batched).
+    ///
+    /// # Arguments
+    /// process - The process to work on
+    /// tasks - The due tasks with their identifier
+    ///
+    /// # Returns
+    /// The result of every task, in the same order as the tasks.
+    fn run(process: &dyn MemoryBackend, tasks: &mut [(u64, Self)]) -> Vec<Result<(), Error>>;
+
+    /// Build the report of a task that starts failing.
+    fn error(id: u64, task: &Self, error: Error) -> Self::Error;
+}
+
+/// Represent a task registered in a poller.
+struct PollEntry<T> {
+    /// The task (taken by the worker while it runs).
+    task: Option<T>,
+
+    /// The time between two runs.
+    interval: Duration,
+
+    /// When the task runs next.
+    next_run: Instant,
+
+    /// True if the last run failed (the error was already reported).
+    failing: bool,
+}
+
+/// Represent the registered tasks.
+struct PollState<T> {
+    /// The tasks, indexed by their identifier.
+    entries: HashMap<u64, PollEntry<T>>,
+
+    /// The identifier of the next registered task.
+    next_id: u64,
+
+    /// True when the worker must stop.
+    stopped: bool,
+}
+
+/// Represent what is shared between a poller, its worker and the registrations.
+struct PollShared<T> {
+    /// The registered tasks.
+    state: Mutex<PollState<T>>,
+
+    /// Notified when a task is registered or when the worker must stop.
+    changed: Condvar,
+
+    /// Held by the worker while the due tasks run (without the state lock), so an unregistration
+    /// can wait for the end of the current run.
+    running: Mutex<()>,
+
+    /// The worker thread.
+    worker_thread: OnceLock<ThreadId>,
+}
+
+/// Represent a background worker thread that runs tasks on the memory of a process, each at its
+/// own interval. A task that starts failing is reported once, until it succeeds again.
+///
+/// The worker stops when the poller is dropped.
+pub struct Poller<T: PollTask> {
+    /// The state shared with the worker and the registrations.
+    shared: Arc<PollShared<T>>,
+
+    /// The worker thread.
+    worker: Option<JoinHandle<()>>,
+}
+
+/// Represent a registered task, run until the registration is dropped.
+pub struct PollRegistration<T: PollTask> {
+    /// The identifier of the task.
+    pub id: u64,
+
+    /// The state shared with the worker.
+    shared: Arc<PollShared<T>>,
+}
+
+impl<T: PollTask> Drop for PollRegistration<T> {
+    fn drop(&mut self) {
+        // The task is dropped without the lock, it may own registrations
+        let entry = lock(&self.shared.state).entries.remove(&self.id);
+
+        drop(entry);
+
+        // The task may be running: wait for the end of the run so it does not touch the process
+        // anymore once dropped (unless dropped by a task of the run, that would wait for itself)
+        if self.shared.worker_thread.get() != Some(&thread::current().id()) {
+            drop(lock(&self.shared.running));
+        }
+    }
+}
+
+impl<T: PollTask> Poller<T> {
+    /// Start a poller (and its worker thread) for the specified process.
+    ///
+    /// # Arguments
+    /// process - The process to work on, shared with the worker
+    ///
+    /// # Returns
+    /// The poller, without any task, and the receiver of the reports of the failing tasks.
+    pub fn new(
+        process: Arc<impl MemoryBackend + Send + Sync + 'static>,
+    ) -> (Poller<T>, Receiver<T::Error>) {
+        let shared = Arc::new(PollShared {
+            state: Mutex::new(PollState {
+                entries: HashMap::new(),
+                next_id: 0,
+                stopped: false,
+            }),
+            changed: Condvar::new(),
+            running: Mutex::new(()),
+            worker_thread: OnceLock::new(),
+        });
+
+        let (sender, errors) = mpsc::channel();
+
+        let worker_shared = shared.clone();
+        let worker = thread::spawn(move || {
+            let _ = worker_shared.worker_thread.set(thread::current().id());
+
+            run_worker(&*process, &worker_shared, &sender)
+        });
+
+        let poller = Poller {
+            shared,
+            worker: Some(worker),
+        };
+
+        (poller, errors)
+    }
+
+    /// Register a task: it runs now, then at every interval, until the registration is dropped.
+    ///
+    /// # Arguments
+    /// self - The poller
+    /// task - The task to run
+    /// interval - The time between two runs
+    ///
+    /// # Returns
+    /// The registration that keeps the task running
+    pub fn register(self: &Poller<T>, task: T, interval: Duration) -> PollRegistration<T> {
+        let mut state = lock(&self.shared.state);
+        let id = state.next_id;
+
+        state.next_id += 1;
+        state.entries.insert(
+            id,
+            PollEntry {
+                task: Some(task),
+                interval,
+                next_run: Instant::now(),
+                failing: false,
+            },
+        );
+
+        self.shared.changed.notify_all();
+
+        PollRegistration {
+            id,
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T: PollTask> Drop for Poller<T> {
+    fn drop(&mut self) {
+        lock(&self.shared.state).stopped = true;
+        self.shared.changed.notify_all();
+
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Run the tasks when they are due, until the poller is dropped.
+///
+/// # Arguments
+/// process - The process to work on
+/// shared - The state shared with the poller and the registrations
+/// errors - Where the failing tasks are reported
+fn run_worker<T: PollTask>(
+    process: &dyn MemoryBackend,
+    shared: &PollShared<T>,
+    errors: &Sender<T::Error>,
+) {
+    let mut state = lock(&shared.state);
+
+    while !state.stopped {
+        let now = Instant::now();
+        let mut due = Vec::new();
+
+        for (id, entry) in state.entries.iter_mut() {
+            if entry.next_run <= now {
+                if let Some(task) = entry.task.take() {
+                    entry.next_run = now + entry.interval;
+                    due.push((*id, task));
+                }
+            }
+        }
+
+        if !due.is_empty() {
+            // The tasks run without the state lock, so a slow process or a slow task does not
+            // block the registrations
+            let running = lock(&shared.running);
+
+            drop(state);
+
+            let results = T::run(process, &mut due);
+
+            state = lock(&shared.state);
+
+            let mut unregistered = Vec::new();
+
+            for ((id, task), result) in due.into_iter().zip(results) {
+                let entry = match state.entries.get_mut(&id) {
+                    Some(entry) => entry,
+                    None => {
+                        unregistered.push(task);
+                        continue;
+                    }
+                };
+
+                match result {
+                    Ok(()) => entry.failing = false,
+                    Err(error) => {
+                        if !entry.failing {
+                            // The receiver may have been dropped
+                            let _ = errors.send(T::error(id, &task, error));
+                        }
+
+                        entry.failing = true;
+                    }
+                }
+
+                entry.task = Some(task);
+            }
+
+            drop(running);
+
+            // The tasks unregistered while they were running are dropped without the lock, they
+            // may own registrations too
+            if !unregistered.is_empty() {
+                drop(state);
+                drop(unregistered);
+                state = lock(&shared.state);
+            }
+
+            if state.stopped {
+                break;
+            }
+        }
+
+        let next_run = state.entries.values().map(|entry| entry.next_run).min();
+
+        state = match next_run {
+            Some(next_run) => {
+                let timeout = next_run.saturating_duration_since(Instant::now());
+
+                shared
+                    .changed
+                    .wait_timeout(state, timeout)
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .0
+            }
+            None => shared
+                .changed
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner),
+        };
+    }
+}
+
+/// Lock the specified mutex, even if a thread panicked while holding it (the state stays
+/// consistent, a task that panics only stops the worker).
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
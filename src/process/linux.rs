@@ -0,0 +1,553 @@
+use std::ffi::c_void;
+use std::fs::{File, OpenOptions};
+use std::io::{Error, ErrorKind};
+use std::os::unix::fs::FileExt;
+
+use crate::backend::{MemoryBackend, MemoryRegion, Module, Protection, RegionState, RegionType};
+use crate::elf;
+
+/// The default maximum number of processes that can be enumerated.
+static DEFAULT_MAX_NB_PROCESSES: u32 = 1024;
+
+/// The maximum number of ranges of a process_vm_readv call (IOV_MAX).
+const MAX_IOVECS: usize = 1024;
+
+/// Represent a process running on the system.
+pub struct Process {
+    /// The base address of the main module (the executable).
+    pub module_base: usize,
+
+    /// The process identifier.
+    pub pid: u32,
+
+    /// The name of the process.
+    pub name: String,
+
+    /// The memory file of the process, used when process_vm_readv / process_vm_writev cannot be used.
+    mem: Option<File>,
+}
+
+impl MemoryBackend for Process {
+    fn read_bytes(&self, address: usize, buffer: &mut [u8]) -> Result<usize, Error> {
+        let size = buffer.len();
+
+        let local_iov = libc::iovec {
+            iov_base: buffer.as_mut_ptr() as *mut c_void,
+            iov_len: size,
+        };
+
+        let remote_iov = libc::iovec {
+            iov_base: address as *mut c_void,
+            iov_len: size,
+        };
+
+        let result = unsafe {
+            libc::process_vm_readv(self.pid as libc::pid_t, &local_iov, 1, &remote_iov, 1, 0)
+        };
+
+        if result >= 0 {
+            return check_transferred(result as usize, size, address, ErrorKind::UnexpectedEof);
+        }
+
+        let error = Error::last_os_error();
+
+        match (&self.mem, error.raw_os_error()) {
+            (Some(mem), Some(libc::ENOSYS) | Some(libc::EPERM)) => {
+                mem.read_exact_at(buffer, address as u64)?;
+
+                Ok(size)
+            }
+            _ => Err(error),
+        }
+    }
+
+    fn read_many(&self, requests: &mut [(usize, &mut [u8])]) -> Vec<Result<usize, Error>> {
+        let mut results = Vec::with_capacity(requests.len());
+
+        while results.len() < requests.len() {
+            let first = results.len();
+            let count = (requests.len() - first).min(MAX_IOVECS);
+
+            let (local_iov, remote_iov): (Vec<libc::iovec>, Vec<libc::iovec>) = requests
+                [first..first + count]
+                .iter_mut()
+                .map(|(address, buffer)| {
+                    (
+                        libc::iovec {
+                            iov_base: buffer.as_mut_ptr() as *mut c_void,
+                            iov_len: buffer.len(),
+                        },
+                        libc::iovec {
+                            iov_base: *address as *mut c_void,
+                            iov_len: buffer.len(),
+                        },
+                    )
+                })
+                .unzip();
+
+            // Every range is read with a single system call, until the first range that fails
+            let result = unsafe {
+                libc::process_vm_readv(
+                    self.pid as libc::pid_t,
+                    local_iov.as_ptr(),
+                    count as libc::c_ulong,
+                    remote_iov.as_ptr(),
+                    count as libc::c_ulong,
+                    0,
+                )
+            };
+
+            if result < 0 {
+                let error = Error::last_os_error();
+
+                if matches!(error.raw_os_error(), Some(libc::ENOSYS) | Some(libc::EPERM)) {
+                    for (address, buffer) in requests[first..].iter_mut() {
+                        results.push(self.read_bytes(*address, buffer));
+                    }
+
+                    break;
+                }
+            }
+
+            let mut transferred = result.max(0) as usize;
+
+            for (_, buffer) in requests[first..first + count].iter() {
+                if transferred < buffer.len() {
+                    break;
+                }
+
+                transferred -= buffer.len();
+                results.push(Ok(buffer.len()));
+            }
+
+            // Read the range that stopped the transfer alone to get its error
+            if results.len() < first + count {
+                let (address, buffer) = &mut requests[results.len()];
+
+                results.push(self.read_bytes(*address, buffer));
+            }
+        }
+
+        results
+    }
+
+    fn write_bytes(&self, address: usize, buffer: &[u8]) -> Result<usize, Error> {
+        let size = buffer.len();
+
+        let local_iov = libc::iovec {
+            iov_base: buffer.as_ptr() as *mut c_void,
+            iov_len: size,
+        };
+
+        let remote_iov = libc::iovec {
+            iov_base: address as *mut c_void,
+            iov_len: size,
+        };
+
+        let result = unsafe {
+            libc::process_vm_writev(self.pid as libc::pid_t, &local_iov, 1, &remote_iov, 1, 0)
+        };
+
+        if result >= 0 {
+            return check_transferred(result as usize, size, address, ErrorKind::WriteZero);
+        }
+
+        let error = Error::last_os_error();
+
+        // The memory file also allows to write read-only pages (EFAULT with process_vm_writev)
+        match (&self.mem, error.raw_os_error()) {
+            (Some(mem), Some(libc::ENOSYS) | Some(libc::EPERM) | Some(libc::EFAULT)) => {
+                mem.write_all_at(buffer, address as u64)?;
+
+                Ok(size)
+            }
+            _ => Err(error),
+        }
+    }
+
+    fn allocate(&self, _size: usize, _protection: Protection) -> Result<usize, Error> {
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "cannot allocate memory in another process on Linux",
+        ))
+    }
+
+    fn query_region(&self, address: usize) -> Result<MemoryRegion, Error> {
+        let regions = maps_to_regions(&read_maps(self.pid)?);
+
+        let mut free_start = 0;
+
+        for region in regions {
+            if address < region.base_address {
+                return Ok(free_region(free_start, region.base_address));
+            }
+
+            if region.contains(address) {
+                return Ok(region);
+            }
+
+            free_start = region.end_address();
+        }
+
+        Ok(free_region(free_start, usize::MAX))
+    }
+
+    fn regions(&self) -> Result<Vec<MemoryRegion>, Error> {
+        Ok(maps_to_regions(&read_maps(self.pid)?))
+    }
+
+    fn main_module_base(&self) -> usize {
+        self.module_base
+    }
+
+    fn modules(&self) -> Result<Vec<Module>, Error> {
+        modules(self)
+    }
+}
+
+/// Represent a line of the /proc/<pid>/maps file.
+pub(crate) struct MapsEntry {
+    /// The first address of the mapping.
+    pub start: usize,
+
+    /// The address following the last byte of the mapping.
+    pub end: usize,
+
+    /// The permissions of the mapping ("rwxp" notation, p = private, s = shared).
+    pub perms: String,
+
+    /// The mapped file or the pseudo-path ([heap], [stack]...) if any.
+    pub path: Option<String>,
+}
+
+/// Read and parse the memory mappings of the specified process.
+///
+/// # Arguments
+/// pid - The process identifier.
+///
+/// # Returns
+/// If the function succeeds, the return value is the list of mappings sorted by address.
+pub(crate) fn read_maps(pid: u32) -> Result<Vec<MapsEntry>, Error> {
+    let content = std::fs::read_to_string(format!("/proc/{}/maps", pid))?;
+
+    content.lines().map(parse_maps_line).collect()
+}
+
+/// Parse a line of a /proc/<pid>/maps file.
+///
+/// # Arguments
+/// line - The line to parse (ex: "00400000-00452000 r-xp 00000000 08:02 173521 /usr/bin/dbus-daemon").
+///
+/// # Returns
+/// If the function succeeds, the return value is the parsed mapping.
+fn parse_maps_line(line: &str) -> Result<MapsEntry, Error> {
+    let invalid = || {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("invalid maps line: {}", line),
+        )
+    };
+
+    let mut rest = line;
+    let mut fields = [""; 5];
+
+    for field in fields.iter_mut() {
+        rest = rest.trim_start();
+
+        let end = rest.find(' ').unwrap_or(rest.len());
+
+        *field = &rest[..end];
+        rest = &rest[end..];
+    }
+
+    let (start, end) = fields[0].split_once('-').ok_or_else(invalid)?;
+    let path = rest.trim();
+
+    Ok(MapsEntry {
+        start: usize::from_str_radix(start, 16).map_err(|_| invalid())?,
+        end: usize::from_str_radix(end, 16).map_err(|_| invalid())?,
+        perms: fields[1].to_string(),
+        path: if path.is_empty() {
+            None
+        } else {
+            Some(path.to_string())
+        },
+    })
+}
+
+/// Convert the specified mappings into memory regions.
+///
+/// # Arguments
+/// entries - The mappings to convert.
+///
+/// # Returns
+/// The list of memory regions (a mapped file with at least one executable mapping is an image).
+pub(crate) fn maps_to_regions(entries: &[MapsEntry]) -> Vec<MemoryRegion> {
+    let image_paths: Vec<&str> = entries
+        .iter()
+        .filter(|entry| entry.perms.contains('x'))
+        .filter_map(|entry| entry.path.as_deref())
+        .filter(|path| path.starts_with('/') || *path == "[vdso]")
+        .collect();
+
+    entries
+        .iter()
+        .map(|entry| {
+            let mut protection = Protection::NONE;
+
+            if entry.perms.contains('r') {
+                protection = protection | Protection::READ;
+            }
+
+            if entry.perms.contains('w') {
+                protection = protection | Protection::WRITE;
+            }
+
+            if entry.perms.contains('x') {
+                protection = protection | Protection::EXECUTE;
+            }
+
+            let kind = match entry.path.as_deref() {
+                Some(path) if image_paths.contains(&path) => RegionType::Image,
+                Some(path) if path.starts_with('/') => RegionType::Mapped,
+                _ if entry.perms.contains('s') => RegionType::Mapped,
+                _ => RegionType::Private,
+            };
+
+            // PROT_NONE mappings are used to reserve address space (guard pages, allocator arenas...)
+            let state = if protection == Protection::NONE {
+                RegionState::Reserved
+            } else {
+                RegionState::Committed
+            };
+
+            MemoryRegion {
+                base_address: entry.start,
+                size: entry.end - entry.start,
+                protection,
+                state,
+                kind,
+                path: entry.path.clone(),
+            }
+        })
+        .collect()
+}
+
+/// Create a free region that covers the specified range.
+fn free_region(start: usize, end: usize) -> MemoryRegion {
+    MemoryRegion {
+        base_address: start,
+        size: end - start,
+        protection: Protection::NONE,
+        state: RegionState::Free,
+        kind: RegionType::Unknown,
+        path: None,
+    }
+}
+
+/// Check that a transfer between the current process and the target process is complete.
+///
+/// # Arguments
+/// transferred - The number of bytes transferred.
+/// size - The number of bytes that must be transferred.
+/// address - The remote address of the transfer.
+/// kind - The kind of error to return if the transfer is partial.
+///
+/// # Returns
+/// If the transfer is complete, the return value is the number of bytes transferred.
+fn check_transferred(
+    transferred: usize,
+    size: usize,
+    address: usize,
+    kind: ErrorKind,
+) -> Result<usize, Error> {
+    if transferred == size {
+        Ok(transferred)
+    } else {
+        Err(Error::new(
+            kind,
+            format!(
+                "only {} of {} bytes could be transferred at {:#x}",
+                transferred, size, address
+            ),
+        ))
+    }
+}
+
+/// Enumerates PID of running processes on the system.
+///
+/// # Arguments
+/// nb - The maximum number of PID that can be enumerated.
+///
+/// # Returns
+/// If the function succeeds, the return value is a list of process identifiers.
+pub fn enumerate_pid(nb: u32) -> Result<Vec<u32>, Error> {
+    let mut pids = Vec::new();
+
+    for entry in std::fs::read_dir("/proc")? {
+        if pids.len() >= nb as usize {
+            break;
+        }
+
+        if let Some(pid) = entry?
+            .file_name()
+            .to_str()
+            .and_then(|n| n.parse::<u32>().ok())
+        {
+            pids.push(pid);
+        }
+    }
+
+    Ok(pids)
+}
+
+/// Enumerates the thread identifiers of the specified process.
+///
+/// # Arguments
+/// process - The process whose threads are to be enumerated.
+///
+/// # Returns
+/// If the function succeeds, the return value is a list of thread identifiers.
+pub fn threads(process: &Process) -> Result<Vec<u32>, Error> {
+    let mut tids = Vec::new();
+
+    for entry in std::fs::read_dir(format!("/proc/{}/task", process.pid))? {
+        if let Some(tid) = entry?
+            .file_name()
+            .to_str()
+            .and_then(|n| n.parse::<u32>().ok())
+        {
+            tids.push(tid);
+        }
+    }
+
+    tids.sort_unstable();
+
+    Ok(tids)
+}
+
+/// Open the process with the specified identifier.
+///
+/// # Arguments
+/// pid - The process identifier.
+///
+/// # Returns
+/// If the function succeeds, the return value is the opened process.
+pub fn open(pid: u32) -> Result<Process, Error> {
+    let exe_path = std::fs::read_link(format!("/proc/{}/exe", pid))?;
+    let exe_path = exe_path.to_string_lossy().to_string();
+
+    let name = exe_path
+        .rsplit('/')
+        .next()
+        .unwrap_or(&exe_path)
+        .trim_end_matches(" (deleted)")
+        .to_string();
+
+    let module_base = read_maps(pid)?
+        .iter()
+        .filter(|entry| entry.path.as_deref() == Some(exe_path.as_str()))
+        .map(|entry| entry.start)
+        .min()
+        .unwrap_or(0);
+
+    let mem_path = format!("/proc/{}/mem", pid);
+
+    let mem = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(&mem_path)
+        .or_else(|_| File::open(&mem_path))
+        .ok();
+
+    Ok(Process {
+        module_base,
+        pid,
+        name,
+        mem,
+    })
+}
+
+/// Retrieves every module (executable and shared objects) loaded in the specified process.
+///
+/// A module is a mapped file with at least one executable mapping, it spans from its first to its
+/// last mapping.
+///
+/// # Arguments
+/// process - The process whose modules are to be enumerated.
+///
+/// # Returns
+/// If the function succeeds, the return value is the list of modules sorted by address.
+pub fn modules(process: &Process) -> Result<Vec<Module>, Error> {
+    let mut modules: Vec<Module> = Vec::new();
+
+    for region in maps_to_regions(&read_maps(process.pid)?) {
+        let path = match &region.path {
+            Some(path) if region.kind == RegionType::Image && path.starts_with('/') => path.clone(),
+            _ => continue,
+        };
+
+        match modules.iter_mut().find(|module| module.path == path) {
+            Some(module) => module.size = region.end_address() - module.base_address,
+            None => modules.push(Module {
+                name: path.rsplit('/').next().unwrap_or(&path).to_string(),
+                path,
+                base_address: region.base_address,
+                size: region.size,
+                entry_point: 0,
+            }),
+        }
+    }
+
+    for module in modules.iter_mut() {
+        module.entry_point = elf::read_entry_point(process, module.base_address).unwrap_or(0);
+    }
+
+    Ok(modules)
+}
+
+/// Find and return a process with the specified name (case-insensitive).
+///
+/// # Arguments
+/// name - The name of the process to find.
+/// max_search_size - The maximum number of PID that can be enumerated.
+/// _access - Ignored on Linux (the access is granted by the ptrace access mode of the caller).
+///
+/// # Returns
+/// If the function succeeds, the return value is a process with the specified name.
+pub fn get_process_by_name(
+    name: &str,
+    max_search_size: Option<u32>,
+    _access: u32,
+) -> Result<Process, Error> {
+    let all_pid = enumerate_pid(max_search_size.unwrap_or(DEFAULT_MAX_NB_PROCESSES))?;
+
+    for pid in all_pid {
+        let process = match open(pid) {
+            Ok(process) => process,
+            Err(_) => continue,
+        };
+
+        if process.name.to_lowercase() == name.to_lowercase() {
+            return Ok(process);
+        }
+    }
+
+    Err(Error::new(
+        ErrorKind::NotFound,
+        format!("no process named {}", name),
+    ))
+}
+
+/// Retrieves the main module full path of the specified process.
+///
+/// # Arguments
+/// process - The process that contains the module.
+///
+/// # Returns
+/// If the function succeeds, the return value is the full path of the module.
+pub fn get_full_path(process: &Process) -> Result<String, Error> {
+    let path = std::fs::read_link(format!("/proc/{}/exe", process.pid))?;
+
+    Ok(path.to_string_lossy().to_string())
+}
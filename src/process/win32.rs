@@ -0,0 +1,651 @@
+use std::ffi::c_void;
+use std::mem::size_of;
+
+use windows::core::imp::FARPROC;
+use windows::core::Error;
+use windows::Win32::Foundation::{BOOL, HANDLE, HMODULE, MAX_PATH};
+use windows::Win32::System::Diagnostics::Debug::{ReadProcessMemory, WriteProcessMemory};
+use windows::Win32::System::Diagnostics::ToolHelp::{
+    CreateToolhelp32Snapshot, Thread32First, Thread32Next, TH32CS_SNAPTHREAD, THREADENTRY32,
+};
+use windows::Win32::System::Memory::{
+    VirtualAllocEx, VirtualQueryEx, MEMORY_BASIC_INFORMATION, PAGE_PROTECTION_FLAGS,
+    VIRTUAL_ALLOCATION_TYPE,
+};
+use windows::Win32::System::ProcessStatus::{
+    EnumProcessModules, EnumProcessModulesEx, EnumProcesses, GetMappedFileNameW,
+    GetModuleBaseNameW, GetModuleFileNameExA, GetModuleFileNameExW, GetModuleInformation,
+    ENUM_PROCESS_MODULES_EX_FLAGS, MODULEINFO,
+};
+use windows::Win32::System::Threading::{
+    CreateRemoteThread, IsWow64Process, OpenProcess, PROCESS_ACCESS_RIGHTS,
+    PROCESS_QUERY_INFORMATION, PROCESS_VM_READ,
+};
+
+use crate::backend::{MemoryBackend, MemoryRegion, Module, Protection, RegionState, RegionType};
+use crate::handle;
+use crate::windows_api::constants::{
+    DWORD_SIZE, LIST_MODULES_ALL, MEM_COMMIT, MEM_IMAGE, MEM_MAPPED, MEM_PRIVATE, MEM_RESERVE,
+    PAGE_EXECUTE, PAGE_EXECUTE_READ, PAGE_EXECUTE_READWRITE, PAGE_EXECUTE_WRITECOPY, PAGE_GUARD,
+    PAGE_NOACCESS, PAGE_READONLY, PAGE_READWRITE, PAGE_WRITECOPY,
+};
+
+/// The default maximum number of processes that can be enumerated.
+static DEFAULT_MAX_NB_PROCESSES: u32 = 1024;
+
+/// Represent a process running on the system.
+pub struct Process {
+    /// A handle to the process.
+    pub handle: HANDLE,
+
+    /// A handle to the module.
+    pub module_handle: HMODULE,
+
+    /// The process identifier.
+    pub pid: u32,
+
+    /// The name of the process.
+    pub name: String,
+}
+
+impl MemoryBackend for Process {
+    fn read_bytes(&self, address: usize, buffer: &mut [u8]) -> Result<usize, std::io::Error> {
+        let mut lp_number_of_bytes_read = 0;
+
+        unsafe {
+            ReadProcessMemory(
+                self.handle,
+                address as *const c_void,
+                buffer.as_mut_ptr() as *mut c_void,
+                buffer.len(),
+                Some(&mut lp_number_of_bytes_read),
+            )?
+        };
+
+        Ok(lp_number_of_bytes_read)
+    }
+
+    fn write_bytes(&self, address: usize, buffer: &[u8]) -> Result<usize, std::io::Error> {
+        let mut lp_number_of_bytes_written = 0;
+
+        unsafe {
+            WriteProcessMemory(
+                self.handle,
+                address as *const c_void,
+                buffer.as_ptr() as *const c_void,
+                buffer.len(),
+                Some(&mut lp_number_of_bytes_written),
+            )?
+        };
+
+        Ok(lp_number_of_bytes_written)
+    }
+
+    fn allocate(&self, size: usize, protection: Protection) -> Result<usize, std::io::Error> {
+        let lp_base_address = unsafe {
+            VirtualAllocEx(
+                self.handle,
+                None,
+                size,
+                VIRTUAL_ALLOCATION_TYPE(MEM_COMMIT),
+                PAGE_PROTECTION_FLAGS(to_page_protection(protection)),
+            )
+        };
+
+        if lp_base_address.is_null() {
+            Err(std::io::Error::last_os_error())
+        } else {
+            Ok(lp_base_address as usize)
+        }
+    }
+
+    fn query_region(&self, address: usize) -> Result<MemoryRegion, std::io::Error> {
+        let mut lp_buffer = MEMORY_BASIC_INFORMATION::default();
+
+        let result = unsafe {
+            VirtualQueryEx(
+                self.handle,
+                Some(address as *const c_void),
+                &mut lp_buffer,
+                size_of::<MEMORY_BASIC_INFORMATION>(),
+            )
+        };
+
+        if result == 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        let state = if lp_buffer.State.0 == MEM_COMMIT {
+            RegionState::Committed
+        } else if lp_buffer.State.0 == MEM_RESERVE {
+            RegionState::Reserved
+        } else {
+            RegionState::Free
+        };
+
+        let kind = if lp_buffer.Type.0 == MEM_IMAGE {
+            RegionType::Image
+        } else if lp_buffer.Type.0 == MEM_MAPPED {
+            RegionType::Mapped
+        } else if lp_buffer.Type.0 == MEM_PRIVATE {
+            RegionType::Private
+        } else {
+            RegionType::Unknown
+        };
+
+        let path = match kind {
+            RegionType::Image | RegionType::Mapped => {
+                get_mapped_file_name(self, lp_buffer.BaseAddress as usize)
+            }
+            _ => None,
+        };
+
+        Ok(MemoryRegion {
+            base_address: lp_buffer.BaseAddress as usize,
+            size: lp_buffer.RegionSize,
+            protection: from_page_protection(lp_buffer.Protect.0),
+            state,
+            kind,
+            path,
+        })
+    }
+
+    fn main_module_base(&self) -> usize {
+        self.module_handle.0 as usize
+    }
+
+    fn modules(&self) -> Result<Vec<Module>, std::io::Error> {
+        Ok(modules(self)?)
+    }
+}
+
+/// Enumerates PID of running processes on the system.
+///
+/// # Arguments
+/// nb - The maximum number of PID that can be enumerated.
+///
+/// # Returns
+/// If the function succeeds, the return value is a list of process identifiers.
+pub fn enumerate_pid(nb: u32) -> Result<Vec<u32>, Error> {
+    let mut lpid_process = Vec::with_capacity(nb as usize);
+    let mut lpcb_needed = 0;
+
+    let result = unsafe { EnumProcesses(lpid_process.as_mut_ptr(), nb, &mut lpcb_needed) };
+
+    return if result.is_err() {
+        Err(result.unwrap_err())
+    } else {
+        unsafe { lpid_process.set_len((lpcb_needed / DWORD_SIZE) as usize) };
+
+        Ok(lpid_process)
+    };
+}
+
+/// Enumerates the thread identifiers of the specified process.
+///
+/// # Arguments
+/// process - The process whose threads are to be enumerated.
+///
+/// # Returns
+/// If the function succeeds, the return value is a list of thread identifiers.
+pub fn threads(process: &Process) -> Result<Vec<u32>, Error> {
+    let snapshot = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPTHREAD, 0) }?;
+
+    let mut tids = Vec::new();
+    let mut entry = THREADENTRY32 {
+        dwSize: size_of::<THREADENTRY32>() as u32,
+        ..Default::default()
+    };
+
+    let mut result = unsafe { Thread32First(snapshot, &mut entry) };
+
+    while result.is_ok() {
+        if entry.th32OwnerProcessID == process.pid {
+            tids.push(entry.th32ThreadID);
+        }
+
+        result = unsafe { Thread32Next(snapshot, &mut entry) };
+    }
+
+    handle::close(snapshot)?;
+
+    Ok(tids)
+}
+
+/// Determines if the specified process is running under WOW64 (is 64 bits program).
+///
+/// # Arguments
+/// process_handle - A handle to the process.
+///
+/// # Returns
+/// If the function succeeds, the return value is true if the process is running under WOW64.
+pub fn is_64bit_process(process_handle: HANDLE) -> Result<BOOL, Error> {
+    let mut is_wow64 = BOOL::from(false);
+
+    let result = unsafe { IsWow64Process(process_handle, &mut is_wow64) };
+
+    return if result.is_err() {
+        Err(result.unwrap_err())
+    } else {
+        Ok(is_wow64)
+    };
+}
+
+/// Open the specified process and return a handle to it.
+///
+/// # Arguments
+/// pid - The process identifier.
+/// access - The access to the process.
+///
+/// # Returns
+/// If the function succeeds, the return value is a handle to the process.
+pub fn open(pid: u32, access: PROCESS_ACCESS_RIGHTS) -> Result<HANDLE, Error> {
+    let handle = unsafe { OpenProcess(access, false, pid) };
+
+    return if handle.is_err() {
+        Err(handle.unwrap_err())
+    } else {
+        Ok(handle.unwrap())
+    };
+}
+
+/// Enumerates the modules associated with the specified process (32 bits / 64 bits).
+///
+/// # Arguments
+/// process_handle - A handle to the process whose modules are to be enumerated.
+///
+/// # Returns
+/// If the function succeeds, the return value is an array of module handles.
+pub fn enum_modules(process_handle: HANDLE) -> Result<HMODULE, Error> {
+    let result = is_64bit_process(process_handle);
+
+    if result.is_err() {
+        return Err(result.unwrap_err());
+    }
+
+    return if result.unwrap().as_bool() {
+        enum_modules_64bits(process_handle)
+    } else {
+        enum_modules_32bits(process_handle)
+    };
+}
+
+pub fn get_module_base_name(
+    process_handle: HANDLE,
+    module_handle: HMODULE,
+) -> Result<String, Error> {
+    let mut lp_base_name = [0; MAX_PATH as usize];
+
+    let result = unsafe { GetModuleBaseNameW(process_handle, module_handle, &mut lp_base_name) };
+
+    return if result == 0 {
+        Err(Error::from_win32())
+    } else {
+        Ok(String::from_utf16_lossy(&lp_base_name[0..result as usize]))
+    };
+}
+
+/// Enumerates all the modules associated with the specified process (32 bits and 64 bits modules).
+///
+/// # Arguments
+/// process_handle - A handle to the process whose modules are to be enumerated.
+///
+/// # Returns
+/// If the function succeeds, the return value is the list of module handles (the first one is the executable).
+pub fn enum_all_modules(process_handle: HANDLE) -> Result<Vec<HMODULE>, Error> {
+    let mut lpcb_needed = 0;
+
+    unsafe {
+        EnumProcessModulesEx(
+            process_handle,
+            std::ptr::null_mut(),
+            0,
+            &mut lpcb_needed,
+            ENUM_PROCESS_MODULES_EX_FLAGS(LIST_MODULES_ALL),
+        )?
+    };
+
+    // Some margin in case modules are loaded between the two calls
+    let mut lph_module = vec![HMODULE::default(); lpcb_needed as usize / size_of::<HMODULE>() + 16];
+
+    unsafe {
+        EnumProcessModulesEx(
+            process_handle,
+            lph_module.as_mut_ptr(),
+            (lph_module.len() * size_of::<HMODULE>()) as u32,
+            &mut lpcb_needed,
+            ENUM_PROCESS_MODULES_EX_FLAGS(LIST_MODULES_ALL),
+        )?
+    };
+
+    lph_module.truncate(lpcb_needed as usize / size_of::<HMODULE>());
+
+    Ok(lph_module)
+}
+
+/// Retrieves the full path of the specified module.
+///
+/// # Arguments
+/// process_handle - A handle to the process that contains the module.
+/// module_handle - A handle to the module.
+///
+/// # Returns
+/// If the function succeeds, the return value is the full path of the module file.
+pub fn get_module_file_name(
+    process_handle: HANDLE,
+    module_handle: HMODULE,
+) -> Result<String, Error> {
+    let mut lp_file_name = [0; 1024];
+
+    let result = unsafe { GetModuleFileNameExW(process_handle, module_handle, &mut lp_file_name) };
+
+    if result == 0 {
+        Err(Error::from_win32())
+    } else {
+        Ok(String::from_utf16_lossy(&lp_file_name[0..result as usize]))
+    }
+}
+
+/// Retrieves every module loaded in the specified process.
+///
+/// # Arguments
+/// process - The process whose modules are to be enumerated.
+///
+/// # Returns
+/// If the function succeeds, the return value is the list of modules (the first one is the executable).
+pub fn modules(process: &Process) -> Result<Vec<Module>, Error> {
+    let mut modules = Vec::new();
+
+    for module_handle in enum_all_modules(process.handle)? {
+        let mut lp_mod_info = MODULEINFO::default();
+
+        unsafe {
+            GetModuleInformation(
+                process.handle,
+                module_handle,
+                &mut lp_mod_info,
+                size_of::<MODULEINFO>() as u32,
+            )?
+        };
+
+        modules.push(Module {
+            name: get_module_base_name(process.handle, module_handle)?,
+            path: get_module_file_name(process.handle, module_handle)?,
+            base_address: lp_mod_info.lpBaseOfDll as usize,
+            size: lp_mod_info.SizeOfImage as usize,
+            entry_point: lp_mod_info.EntryPoint as usize,
+        });
+    }
+
+    Ok(modules)
+}
+
+/// Find and return a process with the specified name (case-insensitive).
+///
+/// # Arguments
+/// name - The name of the process to find.
+/// max_search_size - The maximum number of PID that can be enumerated.
+/// access - The access to the process.
+///
+/// # Returns
+/// If the function succeeds, the return value is a process with the specified name.
+pub fn get_process_by_name(
+    name: &str,
+    max_search_size: Option<u32>,
+    access: u32,
+) -> Result<Process, Error> {
+    let r_all_pid = enumerate_pid(max_search_size.unwrap_or(DEFAULT_MAX_NB_PROCESSES));
+
+    if r_all_pid.is_err() {
+        return Err(Error::from_win32());
+    }
+
+    let mut process = Process {
+        handle: HANDLE::default(),
+        module_handle: HMODULE::default(),
+        pid: 0,
+        name: String::default(),
+    };
+
+    for pid in r_all_pid.unwrap() {
+        let r_handle = open(pid, PROCESS_QUERY_INFORMATION | PROCESS_VM_READ);
+
+        if r_handle.is_err() {
+            continue;
+        }
+
+        let handle = r_handle.unwrap();
+
+        let r_modules = enum_modules(handle);
+
+        if r_modules.is_err() {
+            println!("Cannot enumerate modules for process {}", pid);
+
+            if handle::close(handle).is_err() {
+                println!("Failed to close process handle {}", pid);
+            }
+
+            continue;
+        }
+
+        let h_module = r_modules.unwrap();
+
+        let r_module_base_name = get_module_base_name(handle, h_module);
+
+        if r_module_base_name.is_err() {
+            println!("Cannot get module base name for process {}", pid);
+
+            if handle::close(handle).is_err() {
+                println!("Failed to close process handle {}", pid);
+            }
+
+            continue;
+        }
+
+        let process_name = r_module_base_name.unwrap();
+
+        if process_name.to_lowercase() == name.to_lowercase() {
+            if handle::close(handle).is_err() {
+                println!("Failed to close corresponding process before re-open it with desired access {}", pid);
+            }
+
+            let r_handle = open(pid, PROCESS_ACCESS_RIGHTS(access));
+
+            if r_handle.is_err() {
+                println!("Failed to re-open process with desired access {}", pid);
+                return Err(r_handle.unwrap_err());
+            }
+
+            process.handle = r_handle.unwrap();
+            process.module_handle = h_module;
+            process.pid = pid;
+            process.name = process_name;
+        } else if handle::close(handle).is_err() {
+            println!(
+                "Failed to close process handle that not corresponding {}",
+                pid
+            );
+        }
+    }
+
+    return if process.handle.is_invalid() {
+        Err(Error::from_win32())
+    } else {
+        Ok(process)
+    };
+}
+
+/// Create a new thread that runs in the virtual address space of another process.
+///
+/// # Arguments
+/// process - A handle to the process in which the thread is to be created.
+/// lp_start_address - A pointer to the application-defined function of type LPTHREAD_START_ROUTINE to be executed by the thread.
+/// lp_parameter - A pointer to a variable to be passed to the thread.
+///
+/// # Returns
+/// If the function succeeds, the return value is the handle to the new thread.
+pub fn create_remote_thread(
+    process: &Process,
+    lp_start_address: FARPROC,
+    lp_parameter: *const c_void,
+) -> Result<HANDLE, Error> {
+    let thread_start_routine: Option<
+        unsafe extern "system" fn(lpthreadparameter: *mut c_void) -> u32,
+    > = lp_start_address.map(|f| unsafe { std::mem::transmute(f) });
+
+    return unsafe {
+        CreateRemoteThread(
+            process.handle,
+            None,
+            0,
+            thread_start_routine,
+            Some(lp_parameter),
+            0,
+            None,
+        )
+    };
+}
+
+/// Retrieves the main module full path of the specified process.
+///
+/// # Arguments
+/// process - A handle to the process that contains the module.
+///
+/// # Returns
+/// If the function succeeds, the return value is the full path of the module.
+pub fn get_full_path(process: &Process) -> Result<String, Error> {
+    let mut buffer = [0u8; 1024];
+    let size = unsafe { GetModuleFileNameExA(process.handle, process.module_handle, &mut buffer) };
+
+    return if size == 0 {
+        Err(Error::from_win32())
+    } else {
+        Ok(String::from_utf8_lossy(&buffer[0..size as usize]).to_string())
+    };
+}
+
+/// Enumerates the modules associated with the specified process (32 bits).
+///
+/// # Arguments
+/// process_handle - A handle to the process whose modules are to be enumerated.
+///
+/// # Returns
+/// If the function succeeds, the return value is an array of module handles.
+fn enum_modules_32bits(process_handle: HANDLE) -> Result<HMODULE, Error> {
+    let mut lph_module = HMODULE::default();
+    let mut lpcb_needed = 0;
+
+    let result = unsafe {
+        EnumProcessModules(
+            process_handle,
+            &mut lph_module,
+            size_of::<usize>() as u32,
+            &mut lpcb_needed,
+        )
+    };
+
+    return if result.is_err() {
+        Err(result.unwrap_err())
+    } else {
+        Ok(lph_module)
+    };
+}
+
+/// Enumerates the modules associated with the specified process (64 bits).
+///
+/// # Arguments
+/// handle - A handle to the process whose modules are to be enumerated.
+///
+/// # Returns
+/// If the function succeeds, the return value is an array of module handles.
+fn enum_modules_64bits(process_handle: HANDLE) -> Result<HMODULE, Error> {
+    let mut lph_module = HMODULE::default();
+    let mut lpcb_needed = 0;
+
+    let result = unsafe {
+        EnumProcessModulesEx(
+            process_handle,
+            &mut lph_module,
+            size_of::<usize>() as u32,
+            &mut lpcb_needed,
+            ENUM_PROCESS_MODULES_EX_FLAGS(LIST_MODULES_ALL),
+        )
+    };
+
+    return if result.is_err() {
+        Err(result.unwrap_err())
+    } else {
+        Ok(lph_module)
+    };
+}
+
+/// Retrieves the name of the file that backs the specified address.
+///
+/// # Arguments
+/// process - The process that contains the address.
+/// address - The address to check.
+///
+/// # Returns
+/// The device path of the file (ex: \Device\HarddiskVolume3\Game\game.exe), None if the address is not backed by a file.
+fn get_mapped_file_name(process: &Process, address: usize) -> Option<String> {
+    let mut lp_file_name = [0; MAX_PATH as usize];
+
+    let size =
+        unsafe { GetMappedFileNameW(process.handle, address as *const c_void, &mut lp_file_name) };
+
+    if size == 0 {
+        None
+    } else {
+        Some(String::from_utf16_lossy(&lp_file_name[0..size as usize]))
+    }
+}
+
+/// Convert the specified access rights into Win32 memory protection constants.
+///
+/// # Arguments
+/// protection - The access rights to convert.
+///
+/// # Returns
+/// The corresponding PAGE_* constant.
+fn to_page_protection(protection: Protection) -> u32 {
+    let read = protection.is_readable();
+    let write = protection.is_writable();
+
+    if protection.is_executable() {
+        match (read, write) {
+            (_, true) => PAGE_EXECUTE_READWRITE,
+            (true, false) => PAGE_EXECUTE_READ,
+            (false, false) => PAGE_EXECUTE,
+        }
+    } else {
+        match (read, write) {
+            (_, true) => PAGE_READWRITE,
+            (true, false) => PAGE_READONLY,
+            (false, false) => PAGE_NOACCESS,
+        }
+    }
+}
+
+/// Convert the specified Win32 memory protection constants into access rights.
+///
+/// # Arguments
+/// page_protection - The PAGE_* constants to convert.
+///
+/// # Returns
+/// The corresponding access rights (guard pages are considered as not accessible).
+fn from_page_protection(page_protection: u32) -> Protection {
+    if page_protection & PAGE_GUARD != 0 {
+        return Protection::NONE;
+    }
+
+    match page_protection & 0xFF {
+        p if p == PAGE_READONLY => Protection::READ,
+        p if p == PAGE_READWRITE || p == PAGE_WRITECOPY => Protection::READ | Protection::WRITE,
+        p if p == PAGE_EXECUTE => Protection::EXECUTE,
+        p if p == PAGE_EXECUTE_READ => Protection::READ | Protection::EXECUTE,
+        p if p == PAGE_EXECUTE_READWRITE || p == PAGE_EXECUTE_WRITECOPY => {
+            Protection::READ | Protection::WRITE | Protection::EXECUTE
+        }
+        _ => Protection::NONE,
+    }
+}
@@ -0,0 +1,52 @@
+use std::ffi::c_void;
+use std::io::Error;
+
+use crate::backend::MemoryBackend;
+use crate::memory;
+
+pub use wapi_derive::RemoteStruct;
+
+/// Represent a structure of a remote process that can be read field by field from its address.
+///
+/// Derive it with `#[derive(RemoteStruct)]` and an `#[offset(...)]` on every field, see the
+/// derive macro for the pointer and nested fields.
+pub trait RemoteStruct: Sized {
+    /// Read every field of the struct at the specified address.
+    ///
+    /// # Arguments
+    /// process - The process to read from.
+    /// address - The address of the struct.
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the read struct.
+    fn read_from(process: &(impl MemoryBackend + ?Sized), address: usize) -> Result<Self, Error>;
+}
+
+/// Follow the pointer at the specified address, then the offsets like a multi-level pointer
+/// (used by the derive).
+///
+/// # Arguments
+/// process - The process to read from.
+/// address - The address of the pointer.
+/// offsets - The offsets to apply after the pointer (the last one is added without being followed).
+///
+/// # Returns
+/// If the function succeeds, the return value is the address of the pointed value.
+#[doc(hidden)]
+pub fn follow(
+    process: &(impl MemoryBackend + ?Sized),
+    address: usize,
+    offsets: &[usize],
+) -> Result<usize, Error> {
+    let mut ptr = memory::read::<usize>(process, address as *const c_void)?;
+
+    if let Some((last, offsets)) = offsets.split_last() {
+        for offset in offsets {
+            ptr = memory::read::<usize>(process, ptr.wrapping_add(*offset) as *const c_void)?;
+        }
+
+        ptr = ptr.wrapping_add(*last);
+    }
+
+    Ok(ptr)
+}
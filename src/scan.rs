@@ -0,0 +1,280 @@
+use std::io::Error;
+
+use crate::backend::MemoryBackend;
+use crate::memory;
+
+/// The maximum number of bytes read at once while scanning.
+static SCAN_WINDOW_SIZE: usize = 0x10_0000;
+
+/// Represent a primitive type that can be searched in the memory of a process.
+pub trait ScanValue: Copy + PartialEq + PartialOrd {
+    /// The size of the value in bytes.
+    const SIZE: usize;
+
+    /// Decode a value from the first bytes of the specified buffer (native endianness).
+    fn from_bytes(bytes: &[u8]) -> Self;
+}
+
+macro_rules! impl_scan_value {
+    ($($t:ty),*) => {
+        $(
+            impl ScanValue for $t {
+                const SIZE: usize = std::mem::size_of::<$t>();
+
+                fn from_bytes(bytes: &[u8]) -> Self {
+                    <$t>::from_ne_bytes(bytes[..Self::SIZE].try_into().unwrap())
+                }
+            }
+        )*
+    };
+}
+
+impl_scan_value!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64);
+
+/// Represent the condition that a candidate must satisfy to be kept by a next scan.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScanPredicate<T> {
+    /// The value is different from the previous scan.
+    Changed,
+
+    /// The value is the same as the previous scan.
+    Unchanged,
+
+    /// The value is greater than the previous scan.
+    Increased,
+
+    /// The value is lower than the previous scan.
+    Decreased,
+
+    /// The value is equal to the specified value.
+    EqualTo(T),
+
+    /// The value is between the specified values (inclusive).
+    Between(T, T),
+}
+
+impl<T: ScanValue> ScanPredicate<T> {
+    /// Check if the current value of a candidate satisfies the predicate.
+    ///
+    /// # Arguments
+    /// self - The predicate to check
+    /// previous - The value of the candidate at the previous scan
+    /// current - The current value of the candidate
+    ///
+    /// # Returns
+    /// True if the candidate must be kept
+    pub fn matches(self: &ScanPredicate<T>, previous: T, current: T) -> bool {
+        match *self {
+            ScanPredicate::Changed => current != previous,
+            ScanPredicate::Unchanged => current == previous,
+            ScanPredicate::Increased => current > previous,
+            ScanPredicate::Decreased => current < previous,
+            ScanPredicate::EqualTo(value) => current == value,
+            ScanPredicate::Between(min, max) => current >= min && current <= max,
+        }
+    }
+}
+
+/// Represent the options of a first scan.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScanOptions {
+    /// The alignment of the scanned addresses (None to use the size of the scanned type).
+    pub alignment: Option<usize>,
+
+    /// Only scan the writable regions (the values of a program are rarely in read-only memory).
+    pub writable_only: bool,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        ScanOptions {
+            alignment: None,
+            writable_only: true,
+        }
+    }
+}
+
+/// Represent the candidates found in a window of the address space.
+///
+/// The addresses are stored as 32 bits offsets from the window base to keep big scans compact.
+struct ScanChunk<T> {
+    /// The base address of the window.
+    base_address: usize,
+
+    /// The offsets of the candidates from the base address (sorted).
+    offsets: Vec<u32>,
+
+    /// The values of the candidates at the last scan.
+    values: Vec<T>,
+}
+
+/// Represent the candidates of a scan, narrowed down by successive next scans.
+pub struct Scan<T: ScanValue> {
+    /// The candidates grouped by window of the address space.
+    chunks: Vec<ScanChunk<T>>,
+}
+
+impl<T: ScanValue> Scan<T> {
+    /// Get the number of candidates.
+    pub fn len(self: &Scan<T>) -> usize {
+        self.chunks.iter().map(|chunk| chunk.offsets.len()).sum()
+    }
+
+    /// Check if there is no candidate left.
+    pub fn is_empty(self: &Scan<T>) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Get the addresses of the candidates (sorted).
+    pub fn addresses(self: &Scan<T>) -> impl Iterator<Item = usize> + '_ {
+        self.results().map(|(address, _)| address)
+    }
+
+    /// Get the addresses of the candidates with their values at the last scan (sorted by address).
+    pub fn results(self: &Scan<T>) -> impl Iterator<Item = (usize, T)> + '_ {
+        self.chunks.iter().flat_map(|chunk| {
+            chunk
+                .offsets
+                .iter()
+                .zip(chunk.values.iter())
+                .map(move |(offset, value)| (chunk.base_address + *offset as usize, *value))
+        })
+    }
+
+    /// Read again the candidates and keep only the ones that satisfy the specified predicate.
+    ///
+    /// The candidates that cannot be read anymore are removed.
+    ///
+    /// # Arguments
+    /// self - The scan to narrow
+    /// process - The scanned process
+    /// predicate - The condition that the candidates must satisfy
+    pub fn next_scan(
+        self: &mut Scan<T>,
+        process: &(impl MemoryBackend + ?Sized),
+        predicate: ScanPredicate<T>,
+    ) {
+        let mut buffer = Vec::new();
+
+        self.chunks.retain_mut(|chunk| {
+            // The offsets are sorted, read the whole span of the chunk at once
+            let first = chunk.offsets[0] as usize;
+            let last = chunk.offsets[chunk.offsets.len() - 1] as usize;
+
+            buffer.resize(last - first + T::SIZE, 0);
+
+            let currents: Vec<Option<T>> = if process
+                .read_bytes(chunk.base_address + first, &mut buffer)
+                .is_ok()
+            {
+                chunk
+                    .offsets
+                    .iter()
+                    .map(|offset| Some(T::from_bytes(&buffer[*offset as usize - first..])))
+                    .collect()
+            } else {
+                // A part of the span cannot be read anymore, read the candidates one by one
+                // (coalesced) to only drop the unreadable ones
+                let requests: Vec<(usize, usize)> = chunk
+                    .offsets
+                    .iter()
+                    .map(|offset| (chunk.base_address + *offset as usize, T::SIZE))
+                    .collect();
+
+                memory::read_scatter(process, &requests)
+                    .into_iter()
+                    .map(|result| result.ok().map(|bytes| T::from_bytes(&bytes)))
+                    .collect()
+            };
+
+            let mut kept = 0;
+
+            for (i, current) in currents.into_iter().enumerate() {
+                // The unreadable candidates are removed
+                if let Some(current) =
+                    current.filter(|current| predicate.matches(chunk.values[i], *current))
+                {
+                    chunk.offsets[kept] = chunk.offsets[i];
+                    chunk.values[kept] = current;
+                    kept += 1;
+                }
+            }
+
+            chunk.offsets.truncate(kept);
+            chunk.values.truncate(kept);
+
+            kept > 0
+        });
+    }
+}
+
+/// Search the specified value in every readable region of the specified process.
+///
+/// # Arguments
+/// process - The process to scan
+/// value - The value to search
+/// options - The options of the scan
+///
+/// # Returns
+/// If the function succeeds, the return value is the scan that contains the found addresses.
+pub fn first_scan<T: ScanValue>(
+    process: &(impl MemoryBackend + ?Sized),
+    value: T,
+    options: &ScanOptions,
+) -> Result<Scan<T>, Error> {
+    let alignment = options.alignment.unwrap_or(T::SIZE).max(1);
+
+    let mut chunks = Vec::new();
+    let mut buffer = Vec::new();
+
+    for region in process.regions()? {
+        if !region.is_readable() || (options.writable_only && !region.protection.is_writable()) {
+            continue;
+        }
+
+        let mut window_address = region.base_address;
+
+        while window_address < region.end_address() {
+            let window_end = region
+                .end_address()
+                .min(window_address.saturating_add(SCAN_WINDOW_SIZE));
+
+            // Read a few more bytes to find the values that overlap the next window
+            let read_end = region.end_address().min(window_end + T::SIZE - 1);
+
+            buffer.resize(read_end - window_address, 0);
+
+            if process.read_bytes(window_address, &mut buffer).is_ok() {
+                let mut chunk = ScanChunk {
+                    base_address: window_address,
+                    offsets: Vec::new(),
+                    values: Vec::new(),
+                };
+
+                // The first aligned address of the window
+                let mut offset = (alignment - window_address % alignment) % alignment;
+
+                while offset < window_end - window_address && offset + T::SIZE <= buffer.len() {
+                    let current = T::from_bytes(&buffer[offset..]);
+
+                    if current == value {
+                        chunk.offsets.push(offset as u32);
+                        chunk.values.push(current);
+                    }
+
+                    offset += alignment;
+                }
+
+                if !chunk.offsets.is_empty() {
+                    chunk.offsets.shrink_to_fit();
+                    chunk.values.shrink_to_fit();
+                    chunks.push(chunk);
+                }
+            }
+
+            window_address = window_end;
+        }
+    }
+
+    Ok(Scan { chunks })
+}
@@ -0,0 +1,467 @@
+use std::fmt;
+use std::io::{Error, ErrorKind};
+use std::str::FromStr;
+
+use memchr::memmem;
+
+use crate::backend::{MemoryBackend, MemoryRegion};
+use crate::memory::{self, MultiLevelPointer, PointerBase};
+
+/// The maximum number of bytes read at once while searching a pattern.
+static SEARCH_WINDOW_SIZE: usize = 0x10_0000;
+
+/// Represent a byte pattern with wildcards (ex: "48 8B 05 ?? ?? ?? ?? 48 85 C0").
+///
+/// A byte at position i of the memory matches if `memory[i] & masks[i] == bytes[i]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern {
+    /// The expected bits of each byte (the wildcard bits are set to 0).
+    bytes: Vec<u8>,
+
+    /// The bits that must be compared for each byte (0x00 = "??", 0xF0 = "4?", 0x0F = "?8").
+    masks: Vec<u8>,
+}
+
+impl Pattern {
+    /// Parse an IDA / x64dbg style signature.
+    ///
+    /// Every token is separated by spaces and is either a byte ("8B"), a full wildcard ("??" or "?")
+    /// or a nibble wildcard ("4?", "?B").
+    ///
+    /// # Arguments
+    /// signature - The signature to parse
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the parsed pattern.
+    pub fn parse(signature: &str) -> Result<Pattern, Error> {
+        let mut bytes = Vec::new();
+        let mut masks = Vec::new();
+
+        for (index, token) in signature.split_whitespace().enumerate() {
+            let invalid = || {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("invalid signature token #{} \"{}\"", index, token),
+                )
+            };
+
+            let (byte, mask) = match token {
+                "?" | "??" => (0, 0x00),
+                _ if token.len() == 2 => {
+                    let mut byte = 0;
+                    let mut mask = 0;
+
+                    for c in token.chars() {
+                        byte <<= 4;
+                        mask <<= 4;
+
+                        if c != '?' {
+                            byte |= c.to_digit(16).ok_or_else(invalid)? as u8;
+                            mask |= 0x0F;
+                        }
+                    }
+
+                    (byte, mask)
+                }
+                _ => return Err(invalid()),
+            };
+
+            bytes.push(byte);
+            masks.push(mask);
+        }
+
+        if bytes.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidInput, "empty signature"));
+        }
+
+        Ok(Pattern { bytes, masks })
+    }
+
+    /// Get the number of bytes matched by the pattern.
+    pub fn len(self: &Pattern) -> usize {
+        self.bytes.len()
+    }
+
+    /// Check if the pattern is empty (never true for a parsed pattern).
+    pub fn is_empty(self: &Pattern) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Check if the pattern matches the first bytes of the specified buffer.
+    ///
+    /// # Arguments
+    /// self - The pattern
+    /// buffer - The bytes to check
+    ///
+    /// # Returns
+    /// True if the pattern matches
+    pub fn matches(self: &Pattern, buffer: &[u8]) -> bool {
+        buffer.len() >= self.bytes.len()
+            && buffer
+                .iter()
+                .zip(self.bytes.iter().zip(self.masks.iter()))
+                .all(|(byte, (expected, mask))| byte & mask == *expected)
+    }
+
+    /// Find every position where the pattern matches in the specified buffer.
+    ///
+    /// The longest run of bytes without wildcard is searched with a vectorized substring search,
+    /// then the whole pattern is only checked where this run is found.
+    ///
+    /// # Arguments
+    /// self - The pattern
+    /// haystack - The bytes to search into
+    ///
+    /// # Returns
+    /// The sorted offsets of the matches in the buffer.
+    pub fn find_all(self: &Pattern, haystack: &[u8]) -> Vec<usize> {
+        let (anchor_start, anchor_len) = self.anchor();
+
+        if anchor_len == 0 {
+            return (0..(haystack.len() + 1).saturating_sub(self.len()))
+                .filter(|offset| self.matches(&haystack[*offset..]))
+                .collect();
+        }
+
+        let finder = memmem::Finder::new(&self.bytes[anchor_start..anchor_start + anchor_len]);
+
+        let mut offsets = Vec::new();
+        let mut search_start = 0;
+
+        // The search restarts after every hit (not after its end) so the overlapping occurrences
+        // of the anchor are found too (signatures in NOP or INT3 padding)
+        while let Some(hit) = finder.find(&haystack[search_start..]) {
+            let position = search_start + hit;
+
+            if position >= anchor_start && self.matches(&haystack[position - anchor_start..]) {
+                offsets.push(position - anchor_start);
+            }
+
+            search_start = position + 1;
+        }
+
+        offsets
+    }
+
+    /// Find the longest run of bytes without wildcard.
+    ///
+    /// # Returns
+    /// The offset and the length of the run.
+    fn anchor(self: &Pattern) -> (usize, usize) {
+        let mut best = (0, 0);
+        let mut start = 0;
+
+        for (i, mask) in self.masks.iter().enumerate() {
+            if *mask != 0xFF {
+                start = i + 1;
+            } else if i + 1 - start > best.1 {
+                best = (start, i + 1 - start);
+            }
+        }
+
+        best
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = Error;
+
+    fn from_str(signature: &str) -> Result<Self, Self::Err> {
+        Pattern::parse(signature)
+    }
+}
+
+impl fmt::Display for Pattern {
+    /// Format the pattern as an IDA style signature.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (byte, mask)) in self.bytes.iter().zip(self.masks.iter()).enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+
+            let high = if mask & 0xF0 == 0 {
+                String::from("?")
+            } else {
+                format!("{:X}", byte >> 4)
+            };
+
+            let low = if mask & 0x0F == 0 {
+                String::from("?")
+            } else {
+                format!("{:X}", byte & 0x0F)
+            };
+
+            write!(f, "{}{}", high, low)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Retrieve the readable regions that belong to the module with the specified file name
+/// (case-insensitive, ex: "engine.dll").
+///
+/// # Arguments
+/// process - The process that contains the module
+/// module_name - The file name of the module
+///
+/// # Returns
+/// If the function succeeds, the return value is the list of regions of the module.
+pub fn module_regions(
+    process: &(impl MemoryBackend + ?Sized),
+    module_name: &str,
+) -> Result<Vec<MemoryRegion>, Error> {
+    let module_name = module_name.to_lowercase();
+
+    let regions: Vec<MemoryRegion> = process
+        .regions()?
+        .into_iter()
+        .filter(|region| {
+            region.path.as_deref().is_some_and(|path| {
+                let file_name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+
+                file_name.to_lowercase() == module_name
+            })
+        })
+        .collect();
+
+    if regions.is_empty() {
+        return Err(Error::new(
+            ErrorKind::NotFound,
+            format!("module {} is not loaded", module_name),
+        ));
+    }
+
+    Ok(regions)
+}
+
+/// Search the specified pattern in the readable parts of the specified regions.
+///
+/// # Arguments
+/// process - The process to search into
+/// regions - The regions to search
+/// pattern - The pattern to search
+///
+/// # Returns
+/// If the function succeeds, the return value is the sorted list of the addresses of the matches.
+pub fn find_pattern(
+    process: &(impl MemoryBackend + ?Sized),
+    regions: &[MemoryRegion],
+    pattern: &Pattern,
+) -> Result<Vec<usize>, Error> {
+    let mut matches = Vec::new();
+    let mut buffer = Vec::new();
+
+    for region in regions.iter().filter(|region| region.is_readable()) {
+        let mut window_address = region.base_address;
+
+        while window_address < region.end_address() {
+            let window_end = region
+                .end_address()
+                .min(window_address.saturating_add(SEARCH_WINDOW_SIZE));
+
+            // Read a few more bytes to find the matches that overlap the next window
+            let read_end = region.end_address().min(window_end + pattern.len() - 1);
+
+            buffer.resize(read_end - window_address, 0);
+
+            if process.read_bytes(window_address, &mut buffer).is_ok() {
+                matches.extend(
+                    pattern
+                        .find_all(&buffer)
+                        .into_iter()
+                        .filter(|offset| *offset < window_end - window_address)
+                        .map(|offset| window_address + offset),
+                );
+            }
+
+            window_address = window_end;
+        }
+    }
+
+    matches.sort_unstable();
+
+    Ok(matches)
+}
+
+/// Search the specified signature in the specified module.
+///
+/// # Arguments
+/// process - The process that contains the module
+/// module_name - The file name of the module to search (ex: "game.exe")
+/// signature - The IDA / x64dbg style signature (ex: "48 8B 05 ?? ?? ?? ?? 48 85 C0")
+///
+/// # Returns
+/// If the function succeeds, the return value is the sorted list of the addresses of the matches.
+pub fn aob_scan(
+    process: &(impl MemoryBackend + ?Sized),
+    module_name: &str,
+    signature: &str,
+) -> Result<Vec<usize>, Error> {
+    let pattern = Pattern::parse(signature)?;
+    let regions = module_regions(process, module_name)?;
+
+    find_pattern(process, &regions, &pattern)
+}
+
+/// Compute the target of a RIP-relative operand (ex: `mov rax, [rip+disp32]`, `call rel32`).
+///
+/// The target is the address of the next instruction plus the signed 32 bits displacement.
+///
+/// # Arguments
+/// process - The process that contains the instruction
+/// address - The address of the instruction
+/// operand_offset - The offset of the displacement in the instruction (3 for `mov rax, [rip+disp32]`)
+/// instruction_length - The length of the instruction (7 for `mov rax, [rip+disp32]`)
+///
+/// # Returns
+/// If the function succeeds, the return value is the absolute address targeted by the instruction.
+pub fn resolve_relative(
+    process: &(impl MemoryBackend + ?Sized),
+    address: usize,
+    operand_offset: usize,
+    instruction_length: usize,
+) -> Result<usize, Error> {
+    let displacement = memory::read::<i32>(
+        process,
+        address.wrapping_add(operand_offset) as *const std::ffi::c_void,
+    )?;
+
+    Ok(address
+        .wrapping_add(instruction_length)
+        .wrapping_add_signed(displacement as isize))
+}
+
+/// Represent how the address of a signature match is turned into the searched address.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resolution {
+    /// The searched address is the address of the match.
+    Match,
+
+    /// The match is an instruction with a RIP-relative operand and the searched address is its target.
+    Relative {
+        /// The offset of the instruction in the signature.
+        instruction_offset: usize,
+
+        /// The offset of the 32 bits displacement in the instruction.
+        operand_offset: usize,
+
+        /// The length of the instruction.
+        instruction_length: usize,
+    },
+}
+
+impl Resolution {
+    /// The resolution of a `mov reg, [rip+disp32]` instruction (ex: 48 8B 05 ?? ?? ?? ??) at the start of the signature.
+    pub const MOV_RIP: Resolution = Resolution::Relative {
+        instruction_offset: 0,
+        operand_offset: 3,
+        instruction_length: 7,
+    };
+
+    /// The resolution of a `call rel32` instruction (ex: E8 ?? ?? ?? ??) at the start of the signature.
+    pub const CALL: Resolution = Resolution::Relative {
+        instruction_offset: 0,
+        operand_offset: 1,
+        instruction_length: 5,
+    };
+}
+
+/// Represent the definition of an address that is found with a signature.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Signature {
+    /// The file name of the module to search (ex: "game.exe").
+    pub module_name: String,
+
+    /// The pattern to search.
+    pub pattern: Pattern,
+
+    /// How the address of the first match is turned into the searched address.
+    pub resolution: Resolution,
+
+    /// The offset to add to the resolved address.
+    pub offset: isize,
+}
+
+impl Signature {
+    /// Create a new signature definition.
+    ///
+    /// # Arguments
+    /// module_name - The file name of the module to search
+    /// signature - The IDA / x64dbg style signature
+    /// resolution - How the address of the first match is turned into the searched address
+    ///
+    /// # Returns
+    /// If the signature can be parsed, the return value is the signature definition.
+    pub fn new(
+        module_name: &str,
+        signature: &str,
+        resolution: Resolution,
+    ) -> Result<Signature, Error> {
+        Ok(Signature {
+            module_name: module_name.to_string(),
+            pattern: Pattern::parse(signature)?,
+            resolution,
+            offset: 0,
+        })
+    }
+
+    /// Search the signature and resolve the searched address from the first match.
+    ///
+    /// # Arguments
+    /// self - The signature definition
+    /// process - The process that contains the module
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the resolved absolute address.
+    pub fn resolve(
+        self: &Signature,
+        process: &(impl MemoryBackend + ?Sized),
+    ) -> Result<usize, Error> {
+        let regions = module_regions(process, &self.module_name)?;
+
+        let found = *find_pattern(process, &regions, &self.pattern)?
+            .first()
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::NotFound,
+                    format!(
+                        "signature \"{}\" not found in {}",
+                        self.pattern, self.module_name
+                    ),
+                )
+            })?;
+
+        let address = match self.resolution {
+            Resolution::Match => found,
+            Resolution::Relative {
+                instruction_offset,
+                operand_offset,
+                instruction_length,
+            } => resolve_relative(
+                process,
+                found.wrapping_add(instruction_offset),
+                operand_offset,
+                instruction_length,
+            )?,
+        };
+
+        Ok(address.wrapping_add_signed(self.offset))
+    }
+
+    /// Build a multi-level pointer whose base is the address resolved by the signature.
+    ///
+    /// # Arguments
+    /// self - The signature definition (the resolved address must contain the first pointer of the chain)
+    /// offsets - The offsets of the multi-level pointer
+    ///
+    /// # Returns
+    /// The multi-level pointer (the signature is resolved each time the pointer is followed).
+    pub fn to_multi_level_pointer(self: &Signature, offsets: Vec<usize>) -> MultiLevelPointer {
+        MultiLevelPointer {
+            base: PointerBase::Signature(self.clone()),
+            base_address: 0,
+            offsets,
+        }
+    }
+}
@@ -0,0 +1,377 @@
+use std::ffi::c_void;
+use std::io::{Error, ErrorKind};
+use std::mem::size_of;
+
+use crate::backend::MemoryBackend;
+use crate::memory;
+
+/// The maximum number of bytes read at once while searching the end of a null-terminated string
+/// (the reads never cross a page, so a string at the end of a region can be read).
+static STRING_CHUNK_SIZE: usize = 0x1000;
+
+/// The number of characters of the inline buffer of MSVC std::string (SSO), the null included.
+static MSVC_STRING_BUFFER_SIZE: usize = 16;
+
+/// The number of characters of the inline buffer of MSVC std::wstring (SSO), the null included.
+static MSVC_WSTRING_BUFFER_SIZE: usize = 8;
+
+/// Represent how a string is stored in memory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StringLayout {
+    /// Null-terminated UTF-8 string (char*).
+    CString,
+
+    /// Null-terminated UTF-16 string (wchar_t* on Windows).
+    Utf16,
+
+    /// UTF-8 bytes preceded by their number, stored on the specified number of bytes (1, 2, 4
+    /// or 8, little-endian).
+    Prefixed(usize),
+
+    /// MSVC std::string.
+    MsvcString,
+
+    /// MSVC std::wstring.
+    MsvcWString,
+
+    /// libstdc++ std::string (GCC 5 ABI).
+    LibstdcppString,
+}
+
+/// Read the string stored with the specified layout at the specified address.
+///
+/// # Arguments
+/// process - The process to read from.
+/// address - The address of the string (or of the std::string object).
+/// layout - How the string is stored.
+/// max_length - The maximum number of characters (code units) of the string.
+///
+/// # Returns
+/// If the function succeeds, the return value is the string.
+pub fn read_string(
+    process: &(impl MemoryBackend + ?Sized),
+    address: usize,
+    layout: StringLayout,
+    max_length: usize,
+) -> Result<String, Error> {
+    match layout {
+        StringLayout::CString => read_c_string(process, address, max_length),
+        StringLayout::Utf16 => read_utf16_string(process, address, max_length),
+        StringLayout::Prefixed(prefix_size) => {
+            read_prefixed_string(process, address, prefix_size, max_length)
+        }
+        StringLayout::MsvcString => read_msvc_string(process, address, max_length),
+        StringLayout::MsvcWString => read_msvc_wstring(process, address, max_length),
+        StringLayout::LibstdcppString => read_libstdcpp_string(process, address, max_length),
+    }
+}
+
+/// Read the bytes of a null-terminated 8-bit string, in any encoding (ANSI code pages...).
+///
+/// # Arguments
+/// process - The process to read from.
+/// address - The address of the first character.
+/// max_length - The maximum number of bytes of the string (the null excluded).
+///
+/// # Returns
+/// If the function succeeds, the return value is the bytes before the null. It fails with
+/// InvalidData if there is no null in the first max_length + 1 bytes.
+pub fn read_c_bytes(
+    process: &(impl MemoryBackend + ?Sized),
+    address: usize,
+    max_length: usize,
+) -> Result<Vec<u8>, Error> {
+    read_terminated(process, address, 1, max_length)
+}
+
+/// Read a null-terminated UTF-8 string.
+///
+/// # Arguments
+/// process - The process to read from.
+/// address - The address of the first character.
+/// max_length - The maximum number of bytes of the string (the null excluded).
+///
+/// # Returns
+/// If the function succeeds, the return value is the string. It fails with InvalidData if there
+/// is no null in the first max_length + 1 bytes or if the string is not valid UTF-8.
+pub fn read_c_string(
+    process: &(impl MemoryBackend + ?Sized),
+    address: usize,
+    max_length: usize,
+) -> Result<String, Error> {
+    decode_utf8(read_c_bytes(process, address, max_length)?)
+}
+
+/// Read a null-terminated UTF-16 string.
+///
+/// # Arguments
+/// process - The process to read from.
+/// address - The address of the first character.
+/// max_length - The maximum number of UTF-16 code units of the string (the null excluded).
+///
+/// # Returns
+/// If the function succeeds, the return value is the string. It fails with InvalidData if there
+/// is no null in the first max_length + 1 code units or if the string is not valid UTF-16.
+pub fn read_utf16_string(
+    process: &(impl MemoryBackend + ?Sized),
+    address: usize,
+    max_length: usize,
+) -> Result<String, Error> {
+    decode_utf16(&read_terminated(process, address, 2, max_length)?)
+}
+
+/// Read a UTF-8 string preceded by its number of bytes.
+///
+/// # Arguments
+/// process - The process to read from.
+/// address - The address of the length.
+/// prefix_size - The number of bytes of the length (1, 2, 4 or 8, little-endian).
+/// max_length - The maximum number of bytes of the string.
+///
+/// # Returns
+/// If the function succeeds, the return value is the string. It fails with InvalidData if the
+/// length is more than max_length or if the string is not valid UTF-8.
+pub fn read_prefixed_string(
+    process: &(impl MemoryBackend + ?Sized),
+    address: usize,
+    prefix_size: usize,
+    max_length: usize,
+) -> Result<String, Error> {
+    if ![1, 2, 4, 8].contains(&prefix_size) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid length prefix of {} bytes", prefix_size),
+        ));
+    }
+
+    let mut prefix = [0u8; 8];
+
+    process.read_bytes(address, &mut prefix[..prefix_size])?;
+
+    let length = check_length(u64::from_le_bytes(prefix), max_length)?;
+
+    decode_utf8(read_exact(
+        process,
+        address.wrapping_add(prefix_size),
+        length,
+    )?)
+}
+
+/// Read a MSVC std::string: a 16 bytes buffer (or a pointer to the characters when they do not
+/// fit in it), the size then the capacity.
+///
+/// # Arguments
+/// process - The process to read from.
+/// address - The address of the std::string object.
+/// max_length - The maximum number of bytes of the string.
+///
+/// # Returns
+/// If the function succeeds, the return value is the string. It fails with InvalidData if the
+/// object is not valid, if the size is more than max_length or if the string is not valid UTF-8.
+pub fn read_msvc_string(
+    process: &(impl MemoryBackend + ?Sized),
+    address: usize,
+    max_length: usize,
+) -> Result<String, Error> {
+    decode_utf8(read_msvc_characters(
+        process,
+        address,
+        1,
+        MSVC_STRING_BUFFER_SIZE,
+        max_length,
+    )?)
+}
+
+/// Read a MSVC std::wstring: a 8 UTF-16 characters buffer (or a pointer to the characters when
+/// they do not fit in it), the size then the capacity.
+///
+/// # Arguments
+/// process - The process to read from.
+/// address - The address of the std::wstring object.
+/// max_length - The maximum number of UTF-16 code units of the string.
+///
+/// # Returns
+/// If the function succeeds, the return value is the string. It fails with InvalidData if the
+/// object is not valid, if the size is more than max_length or if the string is not valid UTF-16.
+pub fn read_msvc_wstring(
+    process: &(impl MemoryBackend + ?Sized),
+    address: usize,
+    max_length: usize,
+) -> Result<String, Error> {
+    decode_utf16(&read_msvc_characters(
+        process,
+        address,
+        2,
+        MSVC_WSTRING_BUFFER_SIZE,
+        max_length,
+    )?)
+}
+
+/// Read a libstdc++ std::string (GCC 5 ABI): a pointer to the characters (that points inside the
+/// object for short strings), the size then a 16 bytes buffer.
+///
+/// # Arguments
+/// process - The process to read from.
+/// address - The address of the std::string object.
+/// max_length - The maximum number of bytes of the string.
+///
+/// # Returns
+/// If the function succeeds, the return value is the string. It fails with InvalidData if the
+/// size is more than max_length or if the string is not valid UTF-8.
+pub fn read_libstdcpp_string(
+    process: &(impl MemoryBackend + ?Sized),
+    address: usize,
+    max_length: usize,
+) -> Result<String, Error> {
+    let data = memory::read::<usize>(process, address as *const c_void)?;
+    let size = memory::read::<usize>(
+        process,
+        address.wrapping_add(size_of::<usize>()) as *const c_void,
+    )?;
+
+    let length = check_length(size as u64, max_length)?;
+
+    decode_utf8(read_exact(process, data, length)?)
+}
+
+/// Read the characters of a MSVC std::basic_string.
+///
+/// # Arguments
+/// process - The process to read from.
+/// address - The address of the object.
+/// unit - The size of a character.
+/// buffer_size - The number of characters of the inline buffer.
+/// max_length - The maximum number of characters of the string.
+///
+/// # Returns
+/// If the function succeeds, the return value is the bytes of the characters.
+fn read_msvc_characters(
+    process: &(impl MemoryBackend + ?Sized),
+    address: usize,
+    unit: usize,
+    buffer_size: usize,
+    max_length: usize,
+) -> Result<Vec<u8>, Error> {
+    // The buffer is always 16 bytes, followed by the size and the capacity
+    let size = memory::read::<usize>(process, address.wrapping_add(16) as *const c_void)?;
+    let capacity = memory::read::<usize>(
+        process,
+        address.wrapping_add(16 + size_of::<usize>()) as *const c_void,
+    )?;
+
+    if size > capacity || capacity < buffer_size - 1 {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "invalid std::string at {:#x} (size {}, capacity {})",
+                address, size, capacity
+            ),
+        ));
+    }
+
+    let length = check_length(size as u64, max_length)?;
+
+    let data = if capacity < buffer_size {
+        address
+    } else {
+        memory::read::<usize>(process, address as *const c_void)?
+    };
+
+    read_exact(process, data, length * unit)
+}
+
+/// Read the characters before the first null character.
+///
+/// # Arguments
+/// process - The process to read from.
+/// address - The address of the first character.
+/// unit - The size of a character (the null is unit zero bytes at a multiple of unit).
+/// max_length - The maximum number of characters (the null excluded).
+///
+/// # Returns
+/// If the function succeeds, the return value is the bytes of the characters before the null.
+fn read_terminated(
+    process: &(impl MemoryBackend + ?Sized),
+    address: usize,
+    unit: usize,
+    max_length: usize,
+) -> Result<Vec<u8>, Error> {
+    let max_size = max_length
+        .checked_add(1)
+        .and_then(|length| length.checked_mul(unit))
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "maximum length too big"))?;
+
+    let mut bytes = Vec::new();
+    let mut checked = 0;
+
+    while bytes.len() < max_size {
+        let current = address.wrapping_add(bytes.len());
+        let chunk_size =
+            (STRING_CHUNK_SIZE - current % STRING_CHUNK_SIZE).min(max_size - bytes.len());
+        let start = bytes.len();
+
+        bytes.resize(start + chunk_size, 0);
+        process.read_bytes(current, &mut bytes[start..])?;
+
+        while checked + unit <= bytes.len() {
+            if bytes[checked..checked + unit].iter().all(|byte| *byte == 0) {
+                bytes.truncate(checked);
+
+                return Ok(bytes);
+            }
+
+            checked += unit;
+        }
+    }
+
+    Err(Error::new(
+        ErrorKind::InvalidData,
+        format!(
+            "no null terminator in the first {} characters at {:#x}",
+            max_length + 1,
+            address
+        ),
+    ))
+}
+
+/// Read the specified number of bytes.
+fn read_exact(
+    process: &(impl MemoryBackend + ?Sized),
+    address: usize,
+    size: usize,
+) -> Result<Vec<u8>, Error> {
+    let mut bytes = vec![0u8; size];
+
+    process.read_bytes(address, &mut bytes)?;
+
+    Ok(bytes)
+}
+
+/// Check that the length read from memory is not more than the maximum length.
+fn check_length(length: u64, max_length: usize) -> Result<usize, Error> {
+    match usize::try_from(length) {
+        Ok(length) if length <= max_length => Ok(length),
+        _ => Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "string length {} exceeds the maximum of {}",
+                length, max_length
+            ),
+        )),
+    }
+}
+
+/// Decode UTF-8 bytes.
+fn decode_utf8(bytes: Vec<u8>) -> Result<String, Error> {
+    String::from_utf8(bytes).map_err(|error| Error::new(ErrorKind::InvalidData, error))
+}
+
+/// Decode little-endian UTF-16 bytes.
+fn decode_utf16(bytes: &[u8]) -> Result<String, Error> {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+        .collect();
+
+    String::from_utf16(&units).map_err(|error| Error::new(ErrorKind::InvalidData, error))
+}
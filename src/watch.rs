@@ -0,0 +1,316 @@
+use std::io::Error;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::backend::MemoryBackend;
+use crate::memory::{self, MultiLevelPointer};
+use crate::pod::{self, Pod};
+use crate::poll::{PollRegistration, PollTask, Poller};
+
+/// Represent where a watched value is read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WatchTarget {
+    /// The value is at the specified address.
+    Address(usize),
+
+    /// The value is pointed by the multi-level pointer, resolved on every poll. When the pointer
+    /// moves to another object, the value of the new object is the new reference (no event).
+    Pointer(MultiLevelPointer),
+}
+
+/// Represent a change of a watched value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WatchEvent {
+    /// The identifier of the watch (see WatchGuard).
+    pub id: u64,
+
+    /// The address the new value was read at.
+    pub address: usize,
+
+    /// The bytes of the value before the change.
+    pub old: Vec<u8>,
+
+    /// The bytes of the value after the change.
+    pub new: Vec<u8>,
+
+    /// When the change was seen.
+    pub timestamp: Instant,
+}
+
+impl WatchEvent {
+    /// Get the value before the change.
+    ///
+    /// # Arguments
+    /// self - The event
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the old value. It fails if the size of the
+    /// type is not the size of the watch.
+    pub fn old_value<T: Pod>(self: &WatchEvent) -> Result<T, Error> {
+        pod::from_bytes::<T>(&self.old)
+    }
+
+    /// Get the value after the change.
+    ///
+    /// # Arguments
+    /// self - The event
+    ///
+    /// # Returns
+    /// If the function succeeds, the return value is the new value. It fails if the size of the
+    /// type is not the size of the watch.
+    pub fn new_value<T: Pod>(self: &WatchEvent) -> Result<T, Error> {
+        pod::from_bytes::<T>(&self.new)
+    }
+}
+
+/// Represent a watched value that cannot be read anymore (reported once, until it can be read
+/// again).
+#[derive(Debug)]
+pub struct WatchError {
+    /// The identifier of the watch (see WatchGuard).
+    pub id: u64,
+
+    /// Where the value is read.
+    pub target: WatchTarget,
+
+    /// The error of the failed read (of the value or of the pointers).
+    pub error: Error,
+}
+
+/// Receive the changes of a watched value on the worker thread.
+type WatchCallback = Box<dyn FnMut(&WatchEvent) + Send>;
+
+/// Represent where the changes of a watched value are delivered.
+enum WatchSink {
+    /// The changes are sent to the events of the watcher.
+    Channel(Sender<WatchEvent>),
+
+    /// The changes are passed to the callback.
+    Callback(WatchCallback),
+}
+
+/// Represent a value registered in the watcher.
+struct WatchTask {
+    /// Where the value is read.
+    target: WatchTarget,
+
+    /// The number of bytes of the value.
+    size: usize,
+
+    /// Where the changes are delivered.
+    sink: WatchSink,
+
+    /// The address and the bytes of the last read value (none before the first successful read).
+    value: Option<(usize, Vec<u8>)>,
+}
+
+impl WatchTask {
+    /// Keep the value just read and deliver its change, if any.
+    ///
+    /// # Arguments
+    /// self - The watched value
+    /// id - The identifier of the watch
+    /// address - The address the value was read at
+    /// new - The bytes of the value
+    /// timestamp - When the value was read
+    fn update(self: &mut WatchTask, id: u64, address: usize, new: Vec<u8>, timestamp: Instant) {
+        let old = match self.value.replace((address, new.clone())) {
+            // A value read at another address (the pointer moved to another object) is a new
+            // reference, it is not compared
+            Some((old_address, old)) if old_address == address && old != new => old,
+            _ => return,
+        };
+
+        let event = WatchEvent {
+            id,
+            address,
+            old,
+            new,
+            timestamp,
+        };
+
+        match &mut self.sink {
+            // The events are not read anymore once the watcher is dropped
+            WatchSink::Channel(events) => {
+                let _ = events.send(event);
+            }
+            WatchSink::Callback(callback) => callback(&event),
+        }
+    }
+}
+
+impl PollTask for WatchTask {
+    type Error = WatchError;
+
+    fn run(process: &dyn MemoryBackend, tasks: &mut [(u64, WatchTask)]) -> Vec<Result<(), Error>> {
+        let addresses: Vec<Result<usize, Error>> = tasks
+            .iter()
+            .map(|(_, task)| match &task.target {
+                WatchTarget::Address(address) => Ok(*address),
+                WatchTarget::Pointer(mlp) => mlp
+                    .resolve::<u8>(process, 0)
+                    .map(|resolved| resolved.address),
+            })
+            .collect();
+
+        // Every value is read at once, the close ones with a single read
+        let requests: Vec<(usize, usize)> = tasks
+            .iter()
+            .zip(&addresses)
+            .filter_map(|((_, task), address)| {
+                address.as_ref().ok().map(|address| (*address, task.size))
+            })
+            .collect();
+
+        let mut values = memory::read_scatter(process, &requests).into_iter();
+        let timestamp = Instant::now();
+
+        tasks
+            .iter_mut()
+            .zip(addresses)
+            .map(|((id, task), address)| {
+                let address = address?;
+                let new = values.next().unwrap()?;
+
+                task.update(*id, address, new, timestamp);
+
+                Ok(())
+            })
+            .collect()
+    }
+
+    fn error(id: u64, task: &WatchTask, error: Error) -> WatchError {
+        WatchError {
+            id,
+            target: task.target.clone(),
+            error,
+        }
+    }
+}
+
+/// Represent a service that polls values in the memory of a process from a background worker
+/// thread, each at its own interval, and reports their changes until their guard is dropped.
+///
+/// The values that are due at the same time are read together with memory::read_scatter, so
+/// many watches close to each other cost a few reads. The worker stops when the watcher is
+/// dropped.
+pub struct Watcher {
+    /// The changes of the values watched without a callback.
+    pub events: Receiver<WatchEvent>,
+
+    /// The errors of the values that cannot be read anymore.
+    pub errors: Receiver<WatchError>,
+
+    /// Where the changes of the values watched without a callback are sent.
+    event_sender: Sender<WatchEvent>,
+
+    /// The worker that reads the values.
+    poller: Poller<WatchTask>,
+}
+
+/// Represent a watched value, read until the guard is dropped.
+pub struct WatchGuard {
+    /// The identifier of the watched value (as reported in the events and errors).
+    pub id: u64,
+
+    /// The registration of the value in the worker (dropped with the guard).
+    _registration: PollRegistration<WatchTask>,
+}
+
+impl Watcher {
+    /// Start a watcher (and its worker thread) for the specified process.
+    ///
+    /// # Arguments
+    /// process - The process to read from, shared with the worker
+    ///
+    /// # Returns
+    /// The watcher, without any watched value
+    pub fn new(process: Arc<impl MemoryBackend + Send + Sync + 'static>) -> Watcher {
+        let (poller, errors) = Poller::new(process);
+        let (event_sender, events) = mpsc::channel();
+
+        Watcher {
+            events,
+            errors,
+            event_sender,
+            poller,
+        }
+    }
+
+    /// Watch the specified value: it is read now, then at every interval, and its changes are
+    /// sent to the events of the watcher until the returned guard is dropped.
+    ///
+    /// # Arguments
+    /// self - The watcher
+    /// target - Where the value is read
+    /// size - The number of bytes of the value
+    /// interval - The time between two reads
+    ///
+    /// # Returns
+    /// The guard that keeps the value watched
+    pub fn watch(
+        self: &Watcher,
+        target: WatchTarget,
+        size: usize,
+        interval: Duration,
+    ) -> WatchGuard {
+        self.register(
+            target,
+            size,
+            interval,
+            WatchSink::Channel(self.event_sender.clone()),
+        )
+    }
+
+    /// Watch the specified value: it is read now, then at every interval, and its changes are
+    /// passed to the callback (on the worker thread) until the returned guard is dropped. The
+    /// callback can watch other values or drop guards.
+    ///
+    /// # Arguments
+    /// self - The watcher
+    /// target - Where the value is read
+    /// size - The number of bytes of the value
+    /// interval - The time between two reads
+    /// callback - The function called with every change
+    ///
+    /// # Returns
+    /// The guard that keeps the value watched
+    pub fn watch_with(
+        self: &Watcher,
+        target: WatchTarget,
+        size: usize,
+        interval: Duration,
+        callback: impl FnMut(&WatchEvent) + Send + 'static,
+    ) -> WatchGuard {
+        self.register(
+            target,
+            size,
+            interval,
+            WatchSink::Callback(Box::new(callback)),
+        )
+    }
+
+    /// Register a value in the worker.
+    fn register(
+        self: &Watcher,
+        target: WatchTarget,
+        size: usize,
+        interval: Duration,
+        sink: WatchSink,
+    ) -> WatchGuard {
+        let task = WatchTask {
+            target,
+            size,
+            sink,
+            value: None,
+        };
+
+        let registration = self.poller.register(task, interval);
+
+        WatchGuard {
+            id: registration.id,
+            _registration: registration,
+        }
+    }
+}
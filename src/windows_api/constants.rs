@@ -8,4 +8,18 @@ pub static PROCESS_QUERY_INFORMATION: u32 = 0x0400;
 pub static LIST_MODULES_ALL: u32 = 0x03;
 
 pub static MEM_COMMIT: u32 = 0x1000;
+pub static MEM_RESERVE: u32 = 0x2000;
+
+pub static MEM_PRIVATE: u32 = 0x20000;
+pub static MEM_MAPPED: u32 = 0x40000;
+pub static MEM_IMAGE: u32 = 0x1000000;
+
+pub static PAGE_NOACCESS: u32 = 0x01;
+pub static PAGE_READONLY: u32 = 0x02;
+pub static PAGE_READWRITE: u32 = 0x04;
+pub static PAGE_WRITECOPY: u32 = 0x08;
+pub static PAGE_EXECUTE: u32 = 0x10;
+pub static PAGE_EXECUTE_READ: u32 = 0x20;
 pub static PAGE_EXECUTE_READWRITE: u32 = 0x40;
+pub static PAGE_EXECUTE_WRITECOPY: u32 = 0x80;
+pub static PAGE_GUARD: u32 = 0x100;
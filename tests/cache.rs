@@ -0,0 +1,126 @@
+use std::thread;
+use std::time::Duration;
+
+use wapi::backend::{MemoryBackend, Protection};
+use wapi::cache::CachedProcess;
+use wapi::memory::{self, MultiLevelPointer, PointerBase};
+use wapi::mock::MockProcess;
+
+#[test]
+fn cached_pages_are_reused_until_invalidated() {
+    let process = MockProcess::new(0x1_4000_0000);
+
+    process.load(0x1_4000_0010, &0x2000_0000usize.to_le_bytes());
+    process.load(0x2000_0008, &100u32.to_le_bytes());
+
+    let cached = CachedProcess::new(&process, None);
+
+    let ptr = MultiLevelPointer {
+        base: PointerBase::MainModule,
+        base_address: 0x10,
+        offsets: vec![0x8],
+    };
+
+    assert_eq!(ptr.read::<u32>(&cached, 0x0).unwrap(), 100);
+    assert_eq!(cached.cached_pages(), 2);
+
+    // The process changes behind the cache, the cached page is still used
+    process.load(0x2000_0008, &200u32.to_le_bytes());
+
+    assert_eq!(ptr.read::<u32>(&cached, 0x0).unwrap(), 100);
+
+    cached.invalidate(0x2000_0008, 4);
+
+    assert_eq!(cached.cached_pages(), 1);
+    assert_eq!(ptr.read::<u32>(&cached, 0x0).unwrap(), 200);
+
+    cached.invalidate_all();
+
+    assert_eq!(cached.cached_pages(), 0);
+}
+
+#[test]
+fn cached_pages_expire() {
+    let process = MockProcess::new(0);
+
+    process.load(0x2000_0000, &1u32.to_le_bytes());
+
+    let cached = CachedProcess::new(&process, Some(Duration::from_millis(50)));
+
+    assert_eq!(memory::read::<u32>(&cached, 0x2000_0000 as _).unwrap(), 1);
+
+    process.load(0x2000_0000, &2u32.to_le_bytes());
+
+    assert_eq!(memory::read::<u32>(&cached, 0x2000_0000 as _).unwrap(), 1);
+
+    thread::sleep(Duration::from_millis(100));
+
+    assert_eq!(memory::read::<u32>(&cached, 0x2000_0000 as _).unwrap(), 2);
+}
+
+#[test]
+fn expired_pages_are_removed() {
+    let process = MockProcess::new(0);
+
+    process.load(0x2000_0000, &1u32.to_le_bytes());
+    process.load(0x3000_0000, &2u32.to_le_bytes());
+
+    let cached = CachedProcess::new(&process, Some(Duration::from_millis(50)));
+
+    memory::read::<u32>(&cached, 0x2000_0000 as _).unwrap();
+    memory::read::<u32>(&cached, 0x3000_0000 as _).unwrap();
+
+    assert_eq!(cached.cached_pages(), 2);
+
+    thread::sleep(Duration::from_millis(100));
+
+    // Only the page read again is kept, the untouched one is removed
+    memory::read::<u32>(&cached, 0x2000_0000 as _).unwrap();
+
+    assert_eq!(cached.cached_pages(), 1);
+}
+
+#[test]
+fn writes_update_the_cached_pages() {
+    let process = MockProcess::new(0);
+
+    process.load(0x2000_0FFC, &[0u8; 8]);
+
+    let cached = CachedProcess::new(&process, None);
+
+    assert_eq!(memory::read::<u64>(&cached, 0x2000_0FFC as _).unwrap(), 0);
+
+    // The write spans two cached pages
+    memory::write(&cached, 0x2000_0FFC as _, 0x1122_3344_5566_7788u64).unwrap();
+
+    assert_eq!(
+        memory::read::<u64>(&cached, 0x2000_0FFC as _).unwrap(),
+        0x1122_3344_5566_7788
+    );
+    assert_eq!(
+        memory::read::<u64>(&process, 0x2000_0FFC as _).unwrap(),
+        0x1122_3344_5566_7788
+    );
+
+    // A failed write drops the pages it touched
+    process.protect(0x2000_1000, 0x1000, Protection::READ);
+
+    assert!(cached.write_bytes(0x2000_0FFC, &[0xFF; 8]).is_err());
+    assert_eq!(cached.cached_pages(), 0);
+}
+
+#[test]
+fn reads_of_unreadable_pages_are_not_cached() {
+    let process = MockProcess::new(0);
+
+    process.load(0x2000_0000, &5u32.to_le_bytes());
+
+    let cached = CachedProcess::new(&process, None);
+
+    assert!(memory::read::<u32>(&cached, 0x3000_0000 as _).is_err());
+    assert_eq!(cached.cached_pages(), 0);
+
+    // A read that crosses into an unmapped page fails as it would without the cache
+    assert!(cached.read_bytes(0x2000_0FFE, &mut [0u8; 4]).is_err());
+    assert_eq!(memory::read::<u32>(&cached, 0x2000_0000 as _).unwrap(), 5);
+}
@@ -0,0 +1,245 @@
+use std::io::ErrorKind;
+use std::mem::size_of;
+
+use wapi::collection;
+use wapi::mock::MockProcess;
+use wapi::pod::Pod;
+
+const PTR: usize = size_of::<usize>();
+
+#[derive(Clone, Copy, Debug, PartialEq, Pod)]
+#[repr(C)]
+struct Item {
+    id: u32,
+    count: u32,
+}
+
+/// Load the pointers one after the other at the specified address.
+fn load_pointers(process: &MockProcess, address: usize, pointers: &[usize]) {
+    for (i, pointer) in pointers.iter().enumerate() {
+        process.load(address + i * PTR, &pointer.to_le_bytes());
+    }
+}
+
+/// Load the items one after the other at the specified address.
+fn load_items(process: &MockProcess, address: usize, items: &[Item]) {
+    for (i, item) in items.iter().enumerate() {
+        process.load(address + i * 8, wapi::pod::bytes_of(item));
+    }
+}
+
+/// Load a circular doubly linked list of u32 with a sentinel node (next, previous, value).
+fn load_sentinel_list(process: &MockProcess, sentinel: usize, nodes: &[(usize, u32)]) {
+    let mut all = vec![sentinel];
+
+    all.extend(nodes.iter().map(|(node, _)| *node));
+
+    for (i, node) in all.iter().enumerate() {
+        let next = all[(i + 1) % all.len()];
+        let previous = all[(i + all.len() - 1) % all.len()];
+
+        load_pointers(process, *node, &[next, previous]);
+    }
+
+    for (node, value) in nodes {
+        process.load(node + 2 * PTR, &value.to_le_bytes());
+    }
+}
+
+fn items() -> Vec<Item> {
+    (1..=3).map(|id| Item { id, count: id * 10 }).collect()
+}
+
+#[test]
+fn arrays_and_vectors_are_read() {
+    let process = MockProcess::new(0);
+
+    load_items(&process, 0x2100_0000, &items());
+
+    let array: Vec<Item> = collection::read_array(&process, 0x2100_0000, 3, 16)
+        .unwrap()
+        .collect();
+
+    assert_eq!(array, items());
+
+    // first, last, end of storage
+    load_pointers(
+        &process,
+        0x2000_0000,
+        &[0x2100_0000, 0x2100_0018, 0x2100_0020],
+    );
+
+    let vector: Vec<Item> = collection::read_std_vector(&process, 0x2000_0000, 16)
+        .unwrap()
+        .collect();
+
+    assert_eq!(vector, items());
+
+    // The last pointer is after the end of the storage
+    load_pointers(
+        &process,
+        0x2000_0100,
+        &[0x2100_0000, 0x2100_0018, 0x2100_0010],
+    );
+
+    assert_eq!(
+        collection::read_std_vector::<Item>(&process, 0x2000_0100, 16)
+            .err()
+            .unwrap()
+            .kind(),
+        ErrorKind::InvalidData
+    );
+}
+
+#[test]
+fn counts_are_capped() {
+    let process = MockProcess::new(0);
+
+    load_pointers(
+        &process,
+        0x2000_0000,
+        &[0x2100_0000, 0x3100_0000, 0x3100_0000],
+    );
+
+    assert_eq!(
+        collection::read_std_vector::<u32>(&process, 0x2000_0000, 1000)
+            .err()
+            .unwrap()
+            .kind(),
+        ErrorKind::InvalidData
+    );
+    assert_eq!(
+        collection::read_array::<u32>(&process, 0x2100_0000, usize::MAX / 2, usize::MAX)
+            .err()
+            .unwrap()
+            .kind(),
+        ErrorKind::InvalidInput
+    );
+}
+
+#[test]
+fn engine_arrays_are_read() {
+    let process = MockProcess::new(0);
+
+    // TArray: data, count, capacity
+    load_items(&process, 0x2100_0000, &items());
+    load_pointers(&process, 0x2000_0000, &[0x2100_0000]);
+    process.load(0x2000_0000 + PTR, &3i32.to_le_bytes());
+    process.load(0x2000_0000 + PTR + 4, &4i32.to_le_bytes());
+
+    let tarray: Vec<Item> = collection::read_tarray(&process, 0x2000_0000, 16)
+        .unwrap()
+        .collect();
+
+    assert_eq!(tarray, items());
+
+    // Il2CppArray: class, monitor, bounds, count, elements
+    load_pointers(&process, 0x2000_0100, &[0x1234, 0, 0, 3]);
+    load_items(&process, 0x2000_0100 + 4 * PTR, &items());
+
+    let il2cpp: Vec<Item> = collection::read_il2cpp_array(&process, 0x2000_0100, 16)
+        .unwrap()
+        .collect();
+
+    assert_eq!(il2cpp, items());
+}
+
+#[test]
+fn linked_lists_are_read() {
+    let process = MockProcess::new(0);
+
+    // Singly linked list: value, next
+    for (i, node) in [0x2000_0000usize, 0x2000_0100, 0x2000_0200]
+        .iter()
+        .enumerate()
+    {
+        let next = if i == 2 { 0 } else { node + 0x100 };
+
+        process.load(*node, &(i as u32 + 1).to_le_bytes());
+        load_pointers(&process, node + 8, &[next]);
+    }
+
+    let list: Vec<u32> = collection::read_linked_list(&process, 0x2000_0000, 8, 0, 16)
+        .unwrap()
+        .collect();
+
+    assert_eq!(list, vec![1, 2, 3]);
+
+    // The last node points back to the second node
+    load_pointers(&process, 0x2000_0208, &[0x2000_0100]);
+
+    assert_eq!(
+        collection::read_linked_list::<u32>(&process, 0x2000_0000, 8, 0, 16)
+            .err()
+            .unwrap()
+            .kind(),
+        ErrorKind::InvalidData
+    );
+}
+
+#[test]
+fn std_lists_are_read() {
+    let process = MockProcess::new(0);
+    let nodes = [(0x2100_0000, 7), (0x2100_0100, 8), (0x2100_0200, 9)];
+
+    // MSVC: pointer to the sentinel, size
+    load_sentinel_list(&process, 0x2200_0000, &nodes);
+    load_pointers(&process, 0x2000_0000, &[0x2200_0000, 3]);
+
+    let msvc: Vec<u32> = collection::read_msvc_list(&process, 0x2000_0000, 16)
+        .unwrap()
+        .collect();
+
+    assert_eq!(msvc, vec![7, 8, 9]);
+
+    // libstdc++: sentinel, size
+    load_sentinel_list(&process, 0x2000_0100, &nodes);
+    process.load(0x2000_0100 + 2 * PTR, &3usize.to_le_bytes());
+
+    let libstdcpp: Vec<u32> = collection::read_libstdcpp_list(&process, 0x2000_0100, 16)
+        .unwrap()
+        .collect();
+
+    assert_eq!(libstdcpp, vec![7, 8, 9]);
+
+    // The size does not match the nodes
+    load_pointers(&process, 0x2000_0000, &[0x2200_0000, 4]);
+
+    assert_eq!(
+        collection::read_msvc_list::<u32>(&process, 0x2000_0000, 16)
+            .err()
+            .unwrap()
+            .kind(),
+        ErrorKind::InvalidData
+    );
+}
+
+#[test]
+fn msvc_unordered_maps_are_read() {
+    let process = MockProcess::new(0);
+    let nodes = [0x2100_0000usize, 0x2100_0100];
+
+    // Maximum load factor, then the list (pointer to the sentinel, size)
+    load_pointers(&process, 0x2000_0000 + PTR, &[0x2200_0000, 2]);
+    load_sentinel_list(&process, 0x2200_0000, &[(nodes[0], 0), (nodes[1], 0)]);
+
+    // The pairs are (u32 key, u64 value), the value is aligned on 8 bytes
+    for (i, node) in nodes.iter().enumerate() {
+        process.load(node + 2 * PTR, &(i as u32 + 100).to_le_bytes());
+        process.load(node + 2 * PTR + 8, &(i as u64 * 1000).to_le_bytes());
+    }
+
+    let map: Vec<(u32, u64)> = collection::read_msvc_unordered_map(&process, 0x2000_0000, 16)
+        .unwrap()
+        .collect();
+
+    assert_eq!(map, vec![(100, 0), (101, 1000)]);
+
+    assert_eq!(
+        collection::read_msvc_unordered_map::<u32, u64>(&process, 0x2000_0000, 1)
+            .err()
+            .unwrap()
+            .kind(),
+        ErrorKind::InvalidData
+    );
+}
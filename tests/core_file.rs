@@ -0,0 +1,148 @@
+use std::io::ErrorKind;
+
+use wapi::backend::{MemoryBackend, Protection, RegionType};
+use wapi::core_file::CoreFile;
+use wapi::memory::MultiLevelPointer;
+
+/// The path of a checked-in sample file.
+fn sample(name: &str) -> String {
+    format!("{}/tests/data/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+/// game.core is a 64 bits core of the process 4242 (threads 4243 and 4242): /usr/bin/game at
+/// 0x5500000000 (ELF header, code, data), a heap at 0x5600000000 and the code of libc.so.6 at
+/// 0x7F0000000000. Only the ELF header, the data and the heap are dumped.
+fn game_core() -> CoreFile {
+    CoreFile::load(sample("game.core")).unwrap()
+}
+
+#[test]
+fn core_file_describes_the_process() {
+    let core = game_core();
+
+    assert_eq!(core.pid, 4242);
+    assert_eq!(core.name, "game");
+    assert_eq!(core.threads, vec![4243, 4242]);
+}
+
+#[test]
+fn modules_are_derived_from_file_mappings() {
+    let core = game_core();
+
+    let modules = core.modules().unwrap();
+
+    assert_eq!(modules.len(), 2);
+    assert_eq!(modules[0].name, "game");
+    assert_eq!(modules[0].path, "/usr/bin/game");
+    assert_eq!(modules[0].base_address, 0x55_0000_0000);
+    assert_eq!(modules[0].size, 0x4000);
+    assert_eq!(modules[0].entry_point, 0x55_0000_1100);
+    assert_eq!(modules[1].name, "libc.so.6");
+    assert_eq!(core.main_module_base(), 0x55_0000_0000);
+
+    let code = core.query_region(0x55_0000_1000).unwrap();
+
+    assert_eq!(code.kind, RegionType::Image);
+    assert_eq!(code.protection, Protection::READ | Protection::EXECUTE);
+    assert_eq!(
+        core.query_region(0x56_0000_0000).unwrap().kind,
+        RegionType::Private
+    );
+}
+
+#[test]
+fn pointer_chains_can_be_followed_post_mortem() {
+    let core = game_core();
+
+    let mlp: MultiLevelPointer = "\"game\"+3010 -> 50 -> 58".parse().unwrap();
+
+    assert_eq!(mlp.read::<u32>(&core, 0).unwrap(), 800);
+}
+
+#[test]
+fn undumped_memory_cannot_be_read() {
+    let core = game_core();
+    let mut buffer = [0u8; 4];
+
+    assert_eq!(
+        core.read_bytes(0x55_0000_1100, &mut buffer)
+            .unwrap_err()
+            .kind(),
+        ErrorKind::NotFound
+    );
+    assert_eq!(
+        core.write_bytes(0x56_0000_0000, &buffer)
+            .unwrap_err()
+            .kind(),
+        ErrorKind::PermissionDenied
+    );
+}
+
+#[test]
+fn load_rejects_other_files() {
+    let error = CoreFile::load(sample("game.dmp")).err().unwrap();
+
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+}
+
+/// crash.core was written by the Linux kernel when the program of crash.c crashed: a static
+/// executable at 0x400000 with a pointer at 0x402000 to a struct with the HP (800) at +0x58.
+#[test]
+fn read_sample_written_by_linux() {
+    let core = CoreFile::load(sample("crash.core")).unwrap();
+
+    assert_eq!(core.name, "crash");
+    assert_eq!(core.threads, vec![core.pid]);
+
+    let modules = core.modules().unwrap();
+
+    assert_eq!(modules.len(), 1);
+    assert_eq!(modules[0].name, "crash");
+    assert_eq!(modules[0].base_address, 0x40_0000);
+    assert_eq!(modules[0].entry_point, 0x40_1000);
+    assert_eq!(core.main_module_base(), 0x40_0000);
+    assert_eq!(
+        core.query_region(0x40_1000).unwrap().protection,
+        Protection::READ | Protection::EXECUTE
+    );
+
+    let mlp: MultiLevelPointer = "\"crash\"+2000 -> 58".parse().unwrap();
+
+    assert_eq!(mlp.read::<i32>(&core, 0).unwrap(), 800);
+}
+
+#[test]
+fn load_rejects_crafted_files() {
+    let bytes = std::fs::read(sample("crash.core")).unwrap();
+    let find = |pattern: &[u8]| {
+        bytes
+            .windows(pattern.len())
+            .position(|window| window == pattern)
+            .unwrap()
+    };
+
+    // The count of the NT_FILE note (after its type and its padded "CORE" name)
+    let mut huge_count = bytes.clone();
+    let count = find(b"ELIFCORE\0") + 12;
+
+    huge_count[count..count + 8].copy_from_slice(&(u64::MAX / 8).to_le_bytes());
+
+    // The memory size of the vsyscall segment, that then ends after the address space
+    let mut overflowing = bytes.clone();
+    let segment = find(&0xFFFF_FFFF_FF60_0000u64.to_le_bytes());
+
+    overflowing[segment + 24..segment + 32].copy_from_slice(&0x100_0000u64.to_le_bytes());
+
+    let file = std::env::temp_dir().join(format!("wapi-crafted-{}.core", std::process::id()));
+
+    for crafted in [huge_count, overflowing] {
+        std::fs::write(&file, crafted).unwrap();
+
+        assert_eq!(
+            CoreFile::load(&file).err().unwrap().kind(),
+            ErrorKind::InvalidData
+        );
+    }
+
+    std::fs::remove_file(&file).unwrap();
+}
@@ -0,0 +1,21 @@
+/*
+ * Source of crash.core, a core written by the Linux kernel. Regenerate it with:
+ *   gcc -O1 -static -nostdlib -no-pie -fno-asynchronous-unwind-tables -o crash crash.c
+ *   ulimit -c unlimited && ./crash
+ * (with the core pattern "core", the core is written in the current directory)
+ */
+
+struct player {
+    char pad[0x58];
+    int hp;
+};
+
+static struct player player;
+struct player *volatile game_player;
+
+void _start(void)
+{
+    player.hp = 800;
+    game_player = &player;
+    *(volatile int *)0 = 0;
+}
@@ -0,0 +1,133 @@
+use std::io::ErrorKind;
+
+use wapi::backend::{MemoryBackend, Module, Protection, RegionState, RegionType};
+use wapi::dump::{self, DumpMetadata, MemoryDump};
+use wapi::memory::MultiLevelPointer;
+use wapi::mock::{MockProcess, PAGE_SIZE};
+
+/// Dump the specified fake process in a temporary file and load it back.
+fn dump_and_load(process: &MockProcess, metadata: &DumpMetadata, name: &str) -> MemoryDump {
+    let file = std::env::temp_dir().join(format!("wapi-{}-{}.dump", name, std::process::id()));
+
+    dump::write(process, metadata, &file).unwrap();
+    let dump = MemoryDump::load(&file);
+
+    std::fs::remove_file(&file).unwrap();
+
+    dump.unwrap()
+}
+
+fn game_process() -> MockProcess {
+    let mut process = MockProcess::new(0x40_0000);
+
+    process.add_module(Module {
+        name: String::from("game.exe"),
+        path: String::from("C:\\Game\\game.exe"),
+        base_address: 0x40_0000,
+        size: 4 * PAGE_SIZE,
+        entry_point: 0x40_1000,
+    });
+
+    process.load(0x40_2000, &0x2000_0000usize.to_le_bytes());
+    process.load(0x2000_0000 + 0x50, &0x2100_0000usize.to_le_bytes());
+    process.load(0x2100_0000 + 0x58, &800u32.to_le_bytes());
+
+    process
+}
+
+fn metadata(process: &MockProcess) -> DumpMetadata {
+    DumpMetadata {
+        pid: 1234,
+        name: String::from("game.exe"),
+        exe_hash: vec![0xAB; 32],
+        main_module_base: process.main_module_base,
+        modules: process.modules.clone(),
+    }
+}
+
+#[test]
+fn dump_keeps_metadata_and_regions() {
+    let process = game_process();
+    let dump = dump_and_load(&process, &metadata(&process), "metadata");
+
+    assert_eq!(dump.metadata, metadata(&process));
+    assert_eq!(dump.regions().unwrap(), process.regions().unwrap());
+
+    let region = dump.query_region(0x40_2000).unwrap();
+
+    assert_eq!(region.kind, RegionType::Image);
+    assert_eq!(region.protection, Protection::READ);
+    assert_eq!(dump.query_region(0x1000).unwrap().state, RegionState::Free);
+}
+
+#[test]
+fn dump_reads_like_the_process() {
+    let process = game_process();
+    let dump = dump_and_load(&process, &metadata(&process), "read");
+
+    let mlp: MultiLevelPointer = "\"game.exe\"+2000 -> 50 -> 58".parse().unwrap();
+
+    assert_eq!(mlp.read::<u32>(&dump, 0).unwrap(), 800);
+
+    let mut buffer = [0u8; 16];
+
+    assert_eq!(
+        dump.read_bytes(0x2000_1000 - 8, &mut buffer)
+            .unwrap_err()
+            .kind(),
+        ErrorKind::NotFound
+    );
+}
+
+#[test]
+fn dump_skips_unreadable_memory_and_is_read_only() {
+    let process = game_process();
+
+    process.map(0x3000_0000, PAGE_SIZE, Protection::NONE);
+
+    let dump = dump_and_load(&process, &metadata(&process), "read-only");
+
+    let mut buffer = [0u8; 4];
+
+    assert!(dump.read_bytes(0x3000_0000, &mut buffer).is_err());
+    assert_eq!(
+        dump.write_bytes(0x2100_0058, &[0; 4]).unwrap_err().kind(),
+        ErrorKind::PermissionDenied
+    );
+}
+
+#[test]
+fn load_rejects_crafted_data_records() {
+    let process = game_process();
+    let file = std::env::temp_dir().join(format!("wapi-crafted-{}.dump", std::process::id()));
+
+    dump::write(&process, &metadata(&process), &file).unwrap();
+
+    let bytes = std::fs::read(&file).unwrap();
+    let mut record = vec![2u8];
+
+    record.extend_from_slice(&0x2100_0000u64.to_le_bytes());
+
+    let position = bytes
+        .windows(record.len())
+        .position(|window| window == record)
+        .unwrap();
+
+    // A block at the end of the address space and a block too big to be skipped
+    let mut overflowing = bytes.clone();
+    let mut too_big = bytes.clone();
+
+    overflowing[position + 1..position + 9].copy_from_slice(&(u64::MAX - 0xFF).to_le_bytes());
+    too_big[position + 9..position + 17].copy_from_slice(&u64::MAX.to_le_bytes());
+
+    for crafted in [overflowing, too_big] {
+        std::fs::write(&file, crafted).unwrap();
+
+        assert_eq!(
+            MemoryDump::load(&file).err().unwrap().kind(),
+            ErrorKind::InvalidData
+        );
+    }
+
+    std::fs::remove_file(&file).unwrap();
+}
@@ -0,0 +1,216 @@
+use std::ffi::c_void;
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use wapi::backend::Protection;
+use wapi::freeze::{FreezeCondition, FreezeTarget, Freezer};
+use wapi::memory::{self, MultiLevelPointer, PointerBase};
+use wapi::mock::MockProcess;
+
+static INTERVAL: Duration = Duration::from_millis(5);
+
+/// Build a fake process with a pointer at base+0x100 to a struct with the HP at +0x58.
+fn game_process() -> Arc<MockProcess> {
+    let process = MockProcess::new(0x40_0000);
+
+    process.load(0x40_0000 + 0x100, &0x2000_0000usize.to_le_bytes());
+    process.load(0x2000_0000 + 0x58, &800i32.to_le_bytes());
+
+    Arc::new(process)
+}
+
+fn read_hp(process: &MockProcess, address: usize) -> i32 {
+    memory::read::<i32>(process, address as *const c_void).unwrap()
+}
+
+/// Wait until the condition is true (or fail after a second).
+fn wait_until(condition: impl Fn() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(1);
+
+    while !condition() {
+        assert!(Instant::now() < deadline, "timed out");
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn freeze_always_writes_again_after_changes() {
+    let process = game_process();
+    let freezer = Freezer::new(process.clone());
+    let address = 0x2000_0000 + 0x58;
+
+    let _guard = freezer.freeze(
+        FreezeTarget::Address(address),
+        1200i32,
+        INTERVAL,
+        FreezeCondition::Always,
+    );
+
+    wait_until(|| read_hp(&process, address) == 1200);
+
+    process.load(address, &10i32.to_le_bytes());
+
+    wait_until(|| read_hp(&process, address) == 1200);
+}
+
+#[test]
+fn freeze_below_writes_only_under_the_limit() {
+    let process = game_process();
+    let freezer = Freezer::new(process.clone());
+    let address = 0x2000_0000 + 0x58;
+
+    let _guard = freezer.freeze(
+        FreezeTarget::Address(address),
+        500i32,
+        INTERVAL,
+        FreezeCondition::Below(100),
+    );
+
+    thread::sleep(INTERVAL * 4);
+
+    assert_eq!(read_hp(&process, address), 800);
+
+    process.load(address, &50i32.to_le_bytes());
+
+    wait_until(|| read_hp(&process, address) == 500);
+}
+
+#[test]
+fn dropping_the_guard_stops_the_writes() {
+    let process = game_process();
+    let freezer = Freezer::new(process.clone());
+    let address = 0x2000_0000 + 0x58;
+
+    let guard = freezer.freeze(
+        FreezeTarget::Address(address),
+        1200i32,
+        INTERVAL,
+        FreezeCondition::Always,
+    );
+
+    wait_until(|| read_hp(&process, address) == 1200);
+
+    drop(guard);
+
+    process.load(address, &10i32.to_le_bytes());
+
+    thread::sleep(INTERVAL * 4);
+
+    assert_eq!(read_hp(&process, address), 10);
+}
+
+#[test]
+fn freeze_follows_the_pointer_again_on_every_write() {
+    let process = game_process();
+    let freezer = Freezer::new(process.clone());
+
+    let hp_ptr = MultiLevelPointer {
+        base: PointerBase::MainModule,
+        base_address: 0x100,
+        offsets: vec![0x58],
+    };
+
+    let _guard = freezer.freeze(
+        FreezeTarget::Pointer(hp_ptr),
+        1200i32,
+        INTERVAL,
+        FreezeCondition::Always,
+    );
+
+    wait_until(|| read_hp(&process, 0x2000_0000 + 0x58) == 1200);
+
+    // The struct is reallocated
+    process.load(0x3000_0000 + 0x58, &10i32.to_le_bytes());
+    process.load(0x40_0000 + 0x100, &0x3000_0000usize.to_le_bytes());
+
+    wait_until(|| read_hp(&process, 0x3000_0000 + 0x58) == 1200);
+}
+
+#[test]
+fn unwritable_value_is_reported_once() {
+    let process = game_process();
+    let freezer = Freezer::new(process.clone());
+    let address = 0x2000_0000 + 0x58;
+
+    process.protect(0x2000_0000, 0x1000, Protection::READ);
+
+    let guard = freezer.freeze(
+        FreezeTarget::Address(address),
+        1200i32,
+        INTERVAL,
+        FreezeCondition::Always,
+    );
+
+    let error = freezer.errors.recv_timeout(Duration::from_secs(1)).unwrap();
+
+    assert_eq!(error.id, guard.id);
+    assert_eq!(error.target, FreezeTarget::Address(address));
+
+    thread::sleep(INTERVAL * 4);
+
+    assert!(freezer.errors.try_recv().is_err());
+    assert_eq!(read_hp(&process, address), 800);
+}
+
+#[test]
+fn panicking_condition_does_not_poison_the_guards() {
+    let process = game_process();
+    let freezer = Freezer::new(process.clone());
+    let address = 0x2000_0000 + 0x58;
+
+    let panicking = freezer.freeze(
+        FreezeTarget::Address(address),
+        1200i32,
+        INTERVAL,
+        FreezeCondition::When(Box::new(|_| panic!("condition failed"))),
+    );
+
+    thread::sleep(INTERVAL * 4);
+
+    // The worker stopped, but the guards and the freezer can still be dropped
+    let other = freezer.freeze(
+        FreezeTarget::Address(address),
+        1200i32,
+        INTERVAL,
+        FreezeCondition::Always,
+    );
+
+    drop(panicking);
+    drop(other);
+    drop(freezer);
+
+    assert_eq!(read_hp(&process, address), 800);
+}
+
+#[test]
+fn slow_condition_does_not_block_the_freezer() {
+    let process = game_process();
+    let freezer = Freezer::new(process.clone());
+    let (started_sender, started) = mpsc::channel();
+
+    let _slow = freezer.freeze(
+        FreezeTarget::Address(0x2000_0000 + 0x54),
+        1500i32,
+        INTERVAL,
+        FreezeCondition::When(Box::new(move |_| {
+            let _ = started_sender.send(());
+            thread::sleep(Duration::from_millis(500));
+            false
+        })),
+    );
+
+    started.recv_timeout(Duration::from_secs(1)).unwrap();
+
+    let start = Instant::now();
+    let guard = freezer.freeze(
+        FreezeTarget::Address(0x2000_0000 + 0x58),
+        1200i32,
+        INTERVAL,
+        FreezeCondition::Always,
+    );
+
+    assert!(start.elapsed() < Duration::from_millis(250));
+
+    drop(guard);
+}
@@ -0,0 +1,409 @@
+use std::cell::Cell;
+use std::ffi::c_void;
+use std::io::{Error, ErrorKind};
+
+use wapi::backend::{MemoryBackend, MemoryRegion, Module, Protection};
+use wapi::memory::{self, HopFailure, MultiLevelPointer, PointerBase, PointerHop};
+use wapi::mock::MockProcess;
+
+/// Build a fake process with the player chain of re2.exe:
+/// [[[[[base+0x091AD2C0]+0x50]+0x10]+0x20]+0x230]+0x58 = HP, +0x54 = max HP
+fn player_process() -> MockProcess {
+    let process = MockProcess::new(0x1_4000_0000);
+
+    process.load(0x1_4000_0000 + 0x091AD2C0, &0x2000_0000usize.to_le_bytes());
+    process.load(0x2000_0000 + 0x50, &0x2100_0000usize.to_le_bytes());
+    process.load(0x2100_0000 + 0x10, &0x2200_0000usize.to_le_bytes());
+    process.load(0x2200_0000 + 0x20, &0x2300_0000usize.to_le_bytes());
+    process.load(0x2300_0000 + 0x230, &0x2400_0000usize.to_le_bytes());
+    process.load(0x2400_0000 + 0x54, &1200i32.to_le_bytes());
+    process.load(0x2400_0000 + 0x58, &800i32.to_le_bytes());
+
+    process
+}
+
+#[test]
+fn read_multi_level_pointer_follows_every_offset() {
+    let process = player_process();
+
+    let hit_point_ptr = MultiLevelPointer {
+        base: PointerBase::MainModule,
+        base_address: 0x091AD2C0,
+        offsets: vec![0x50, 0x10, 0x20, 0x230],
+    };
+
+    // The last offset is added to the final address, it is not dereferenced
+    let hit_point: usize = hit_point_ptr.read(&process, 0x0).unwrap();
+
+    assert_eq!(hit_point, 0x2400_0000);
+
+    let max_hp_ptr = MultiLevelPointer::from(&hit_point_ptr, vec![0x54]);
+    let hp_ptr = MultiLevelPointer::from(&hit_point_ptr, vec![0x58]);
+
+    assert_eq!(max_hp_ptr.read::<i32>(&process, 0x0).unwrap(), 1200);
+    assert_eq!(hp_ptr.read::<i32>(&process, 0x0).unwrap(), 800);
+    assert_eq!(hp_ptr.read::<i32>(&process, 0x4).unwrap(), 0);
+}
+
+#[test]
+fn read_multi_level_pointer_without_offsets_applies_last_offset() {
+    let process = player_process();
+
+    let ptr = MultiLevelPointer {
+        base: PointerBase::MainModule,
+        base_address: 0x091AD2C0,
+        offsets: vec![],
+    };
+
+    let value: usize = ptr.read(&process, 0x50).unwrap();
+
+    assert_eq!(value, 0x2100_0000);
+}
+
+#[test]
+fn write_multi_level_pointer_writes_pointed_value() {
+    let process = player_process();
+
+    let hp_ptr = MultiLevelPointer {
+        base: PointerBase::MainModule,
+        base_address: 0x091AD2C0,
+        offsets: vec![0x50, 0x10, 0x20, 0x230, 0x58],
+    };
+
+    assert_eq!(hp_ptr.write::<i32>(&process, 0x0, 1200).unwrap(), 4);
+    assert_eq!(
+        memory::read::<i32>(&process, (0x2400_0000 + 0x58) as *const c_void).unwrap(),
+        1200
+    );
+}
+
+#[test]
+fn multi_level_pointer_fails_on_broken_chain() {
+    let process = player_process();
+
+    process.unmap(0x2200_0000, 0x1000);
+
+    let hp_ptr = MultiLevelPointer {
+        base: PointerBase::MainModule,
+        base_address: 0x091AD2C0,
+        offsets: vec![0x50, 0x10, 0x20, 0x230, 0x58],
+    };
+
+    assert!(hp_ptr.read::<i32>(&process, 0x0).is_err());
+    assert!(hp_ptr.write::<i32>(&process, 0x0, 1).is_err());
+}
+
+#[test]
+fn multi_level_pointer_fails_on_null_pointer() {
+    let process = player_process();
+
+    // The null pointer is not followed, even if the null page is mapped
+    process.load(0x2200_0020, &0usize.to_le_bytes());
+    process.load(0x230, &0x2400_0000usize.to_le_bytes());
+
+    let hp_ptr = MultiLevelPointer {
+        base: PointerBase::MainModule,
+        base_address: 0x091AD2C0,
+        offsets: vec![0x50, 0x10, 0x20, 0x230, 0x58],
+    };
+
+    let error = hp_ptr.resolve::<i32>(&process, 0x0).unwrap_err();
+
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+    assert!(hp_ptr.read::<i32>(&process, 0x0).is_err());
+    assert!(hp_ptr.write::<i32>(&process, 0x0, 1).is_err());
+}
+
+#[test]
+fn write_fails_on_read_only_memory() {
+    let process = player_process();
+
+    process.protect(0x2400_0000, 0x1000, Protection::READ);
+
+    assert!(memory::write::<i32>(&process, (0x2400_0000 + 0x58) as *const c_void, 1).is_err());
+    assert_eq!(
+        memory::read::<i32>(&process, (0x2400_0000 + 0x58) as *const c_void).unwrap(),
+        800
+    );
+}
+
+#[test]
+fn multi_level_pointer_base_is_resolved_at_read_time() {
+    let mut process = MockProcess::new(0x40_0000);
+
+    process.add_module(Module {
+        name: String::from("engine.dll"),
+        path: String::from("C:\\Game\\engine.dll"),
+        base_address: 0x7FF0_0000,
+        size: 0x2000,
+        entry_point: 0,
+    });
+
+    process.load(0x7FF0_1234, &0x2000_0000usize.to_le_bytes());
+    process.load(0x2000_0010, &5u32.to_le_bytes());
+
+    let module_ptr = MultiLevelPointer {
+        base: PointerBase::Module(String::from("ENGINE.dll")),
+        base_address: 0x1234,
+        offsets: vec![0x10],
+    };
+
+    let absolute_ptr = MultiLevelPointer {
+        base: PointerBase::Absolute,
+        base_address: 0x7FF0_1234,
+        offsets: vec![0x10],
+    };
+
+    assert_eq!(module_ptr.resolve_base(&process).unwrap(), 0x7FF0_1234);
+    assert_eq!(module_ptr.read::<u32>(&process, 0).unwrap(), 5);
+    assert_eq!(absolute_ptr.read::<u32>(&process, 0).unwrap(), 5);
+
+    // The module is reloaded at another address
+    process.modules[0].base_address = 0x6000_0000;
+    process.load(0x6000_1234, &0x2100_0000usize.to_le_bytes());
+    process.load(0x2100_0010, &6u32.to_le_bytes());
+
+    assert_eq!(module_ptr.read::<u32>(&process, 0).unwrap(), 6);
+    assert_eq!(absolute_ptr.read::<u32>(&process, 0).unwrap(), 5);
+
+    process.modules.clear();
+
+    assert!(module_ptr.read::<u32>(&process, 0).is_err());
+}
+
+/// A fake process that counts the calls to read_many.
+struct CountingProcess {
+    process: MockProcess,
+    reads: Cell<usize>,
+    ranges: Cell<usize>,
+}
+
+impl MemoryBackend for CountingProcess {
+    fn read_bytes(&self, address: usize, buffer: &mut [u8]) -> Result<usize, Error> {
+        self.process.read_bytes(address, buffer)
+    }
+
+    fn read_many(&self, requests: &mut [(usize, &mut [u8])]) -> Vec<Result<usize, Error>> {
+        self.reads.set(self.reads.get() + 1);
+        self.ranges.set(self.ranges.get() + requests.len());
+
+        self.process.read_many(requests)
+    }
+
+    fn write_bytes(&self, address: usize, buffer: &[u8]) -> Result<usize, Error> {
+        self.process.write_bytes(address, buffer)
+    }
+
+    fn allocate(&self, size: usize, protection: Protection) -> Result<usize, Error> {
+        self.process.allocate(size, protection)
+    }
+
+    fn query_region(&self, address: usize) -> Result<MemoryRegion, Error> {
+        self.process.query_region(address)
+    }
+
+    fn main_module_base(&self) -> usize {
+        self.process.main_module_base()
+    }
+}
+
+#[test]
+fn read_scatter_coalesces_close_requests() {
+    let process = CountingProcess {
+        process: player_process(),
+        reads: Cell::new(0),
+        ranges: Cell::new(0),
+    };
+
+    let results = memory::read_scatter(
+        &process,
+        &[
+            (0x2400_0000 + 0x58, 4),
+            (0x2100_0000 + 0x10, 8),
+            (0x2400_0000 + 0x54, 4),
+            (0x2400_0000 + 0x50, 12),
+        ],
+    );
+
+    assert_eq!(results[0].as_ref().unwrap(), &800i32.to_le_bytes());
+    assert_eq!(
+        results[1].as_ref().unwrap(),
+        &0x2200_0000usize.to_le_bytes()
+    );
+    assert_eq!(results[2].as_ref().unwrap(), &1200i32.to_le_bytes());
+    assert_eq!(
+        &results[3].as_ref().unwrap()[4..],
+        &[0xB0, 0x04, 0, 0, 0x20, 0x03, 0, 0]
+    );
+
+    // The three requests on the player are read at once, with the pointer in a single call
+    assert_eq!(process.reads.get(), 1);
+    assert_eq!(process.ranges.get(), 2);
+}
+
+#[test]
+fn read_scatter_reports_errors_per_request() {
+    let process = player_process();
+
+    process.unmap(0x2400_1000, 0x1000);
+
+    let results = memory::read_scatter(
+        &process,
+        &[
+            (0x2400_0058, 4),
+            (0x2400_1000, 4),
+            (0x2400_0FFE, 4),
+            (usize::MAX, 2),
+        ],
+    );
+
+    assert_eq!(results[0].as_ref().unwrap(), &800i32.to_le_bytes());
+    assert_eq!(results[1].as_ref().unwrap_err().kind(), ErrorKind::NotFound);
+    assert_eq!(results[2].as_ref().unwrap_err().kind(), ErrorKind::NotFound);
+    assert_eq!(
+        results[3].as_ref().unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+}
+
+#[test]
+fn resolve_traced_records_every_hop() {
+    let process = player_process();
+
+    let hp_ptr = MultiLevelPointer {
+        base: PointerBase::MainModule,
+        base_address: 0x091AD2C0,
+        offsets: vec![0x50, 0x10, 0x20, 0x230, 0x58],
+    };
+
+    let trace = hp_ptr.resolve_traced(&process, 0x0).unwrap();
+
+    assert_eq!(trace.base_address, 0x1_4000_0000 + 0x091AD2C0);
+    assert_eq!(
+        trace.hops,
+        vec![
+            PointerHop {
+                address: 0x1_4000_0000 + 0x091AD2C0,
+                value: 0x2000_0000
+            },
+            PointerHop {
+                address: 0x2000_0050,
+                value: 0x2100_0000
+            },
+            PointerHop {
+                address: 0x2100_0010,
+                value: 0x2200_0000
+            },
+            PointerHop {
+                address: 0x2200_0020,
+                value: 0x2300_0000
+            },
+            PointerHop {
+                address: 0x2300_0230,
+                value: 0x2400_0000
+            },
+        ]
+    );
+    assert_eq!(trace.address, 0x2400_0058);
+}
+
+#[test]
+fn resolve_traced_reports_the_failing_hop() {
+    let process = player_process();
+
+    let hp_ptr = MultiLevelPointer {
+        base: PointerBase::MainModule,
+        base_address: 0x091AD2C0,
+        offsets: vec![0x50, 0x10, 0x20, 0x230, 0x58],
+    };
+
+    process.unmap(0x2200_0000, 0x1000);
+
+    let error = hp_ptr.resolve_traced(&process, 0x0).unwrap_err();
+
+    assert_eq!(error.hop, 3);
+    assert_eq!(error.address, 0x2200_0020);
+    assert_eq!(error.failure, HopFailure::Unmapped);
+    assert_eq!(error.hops.len(), 3);
+    assert_eq!(Error::from(error).kind(), ErrorKind::NotFound);
+
+    process.map(0x2200_0000, 0x1000, Protection::NONE);
+
+    let error = hp_ptr.resolve_traced(&process, 0x0).unwrap_err();
+
+    assert_eq!(error.failure, HopFailure::Unreadable);
+
+    // A null pointer stops the walk at the hop that read it
+    process.load(0x2100_0010, &0usize.to_le_bytes());
+
+    let error = hp_ptr.resolve_traced(&process, 0x0).unwrap_err();
+
+    assert_eq!(error.hop, 2);
+    assert_eq!(error.address, 0x2100_0010);
+    assert_eq!(error.failure, HopFailure::Null);
+    assert_eq!(error.hops.last().unwrap().value, 0);
+
+    let missing_module_ptr = MultiLevelPointer {
+        base: PointerBase::Module("engine.dll".to_string()),
+        base_address: 0x100,
+        offsets: vec![],
+    };
+
+    let error = missing_module_ptr.resolve_traced(&process, 0).unwrap_err();
+
+    assert_eq!(error.failure, HopFailure::Base);
+    assert!(error.hops.is_empty());
+}
+
+#[test]
+fn resolved_address_is_read_and_written_without_walking() {
+    let process = player_process();
+
+    let hp_ptr = MultiLevelPointer {
+        base: PointerBase::MainModule,
+        base_address: 0x091AD2C0,
+        offsets: vec![0x50, 0x10, 0x20, 0x230, 0x58],
+    };
+
+    let hp = hp_ptr.resolve::<i32>(&process, 0x0).unwrap();
+
+    assert_eq!(hp.address, 0x2400_0058);
+    assert_eq!(hp.pointer_address, 0x2300_0230);
+    assert_eq!(hp.pointer, 0x2400_0000);
+
+    // Breaking the chain does not matter once resolved
+    process.unmap(0x2000_0000, 0x1000);
+
+    assert_eq!(hp.read(&process).unwrap(), 800);
+    assert_eq!(hp.write(&process, 1200).unwrap(), 4);
+    assert_eq!(hp.read(&process).unwrap(), 1200);
+    assert!(!hp.is_stale(&process));
+}
+
+#[test]
+fn resolved_address_is_stale_when_the_object_moves() {
+    let process = player_process();
+
+    let hp_ptr = MultiLevelPointer {
+        base: PointerBase::MainModule,
+        base_address: 0x091AD2C0,
+        offsets: vec![0x50, 0x10, 0x20, 0x230, 0x58],
+    };
+
+    let hp = hp_ptr.resolve::<i32>(&process, 0x0).unwrap();
+
+    // The player is reallocated
+    process.load(0x2500_0058, &900i32.to_le_bytes());
+    process.load(0x2300_0230, &0x2500_0000usize.to_le_bytes());
+
+    assert!(hp.is_stale(&process));
+
+    let hp = hp_ptr.resolve::<i32>(&process, 0x0).unwrap();
+
+    assert!(!hp.is_stale(&process));
+    assert_eq!(hp.read(&process).unwrap(), 900);
+
+    process.unmap(0x2300_0000, 0x1000);
+
+    assert!(hp.is_stale(&process));
+}
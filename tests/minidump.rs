@@ -0,0 +1,215 @@
+use std::io::ErrorKind;
+
+use wapi::backend::{MemoryBackend, Module, Protection, RegionState, RegionType};
+use wapi::memory::{self, MultiLevelPointer};
+use wapi::minidump::{self, Minidump, MinidumpThread};
+use wapi::mock::{MockProcess, PAGE_SIZE};
+
+/// The path of a checked-in sample file.
+fn sample(name: &str) -> String {
+    format!("{}/tests/data/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+/// game.dmp was written by minidump::write: game.exe at 0x140000000 with a PE header, the chain
+/// "game.exe"+2000 -> 50 -> 58 to the HP (800), a no-access page at 0x30000000 and two threads.
+#[test]
+fn read_sample_with_memory_64_list() {
+    let dump = Minidump::load(sample("game.dmp")).unwrap();
+
+    assert_eq!(dump.main_module_base(), 0x1_4000_0000);
+    assert_eq!(dump.modules.len(), 1);
+    assert_eq!(dump.modules[0].name, "game.exe");
+    assert_eq!(dump.modules[0].path, "C:\\Game\\game.exe");
+    assert_eq!(dump.modules[0].size, 4 * PAGE_SIZE);
+    assert_eq!(dump.modules[0].entry_point, 0x1_4000_1230);
+
+    assert_eq!(
+        dump.threads,
+        vec![
+            MinidumpThread {
+                id: 0x1A2C,
+                teb: 0x7FF6_0000_0000,
+                stack_address: 0xA0_0000_0000,
+            },
+            MinidumpThread {
+                id: 0x1B04,
+                teb: 0x7FF6_0000_2000,
+                stack_address: 0,
+            },
+        ]
+    );
+
+    let code = dump.query_region(0x1_4000_1000).unwrap();
+
+    assert_eq!(code.kind, RegionType::Image);
+    assert_eq!(code.protection, Protection::READ | Protection::EXECUTE);
+    assert_eq!(code.path.as_deref(), Some("C:\\Game\\game.exe"));
+
+    let mlp: MultiLevelPointer = "\"game.exe\"+2000 -> 50 -> 58".parse().unwrap();
+
+    assert_eq!(mlp.read::<u32>(&dump, 0).unwrap(), 800);
+
+    let mut buffer = [0u8; 4];
+
+    assert_eq!(
+        dump.query_region(0x3000_0000).unwrap().protection,
+        Protection::NONE
+    );
+    assert!(dump.read_bytes(0x3000_0000, &mut buffer).is_err());
+    assert_eq!(
+        dump.write_bytes(0x2100_0058, &buffer).unwrap_err().kind(),
+        ErrorKind::PermissionDenied
+    );
+}
+
+/// notepad.dmp is a small hand-written dump with a 32 bits memory list and no memory info list:
+/// the first page of notepad.exe (0x7FF710000000, 0x100 bytes) and 32 bytes of stack
+/// (0x5000000000).
+#[test]
+fn read_sample_with_memory_list() {
+    let dump = Minidump::load(sample("notepad.dmp")).unwrap();
+
+    assert_eq!(dump.modules[0].name, "notepad.exe");
+    assert_eq!(dump.modules[0].entry_point, 0x7FF7_1000_1500);
+    assert_eq!(dump.threads[0].id, 42);
+    assert_eq!(dump.threads[0].stack_address, 0x50_0000_0000);
+
+    assert_eq!(
+        memory::read::<[u8; 4]>(&dump, 0x50_0000_0010 as *const _).unwrap(),
+        [0x10, 0x11, 0x12, 0x13]
+    );
+
+    let regions = dump.regions().unwrap();
+
+    assert_eq!(regions.len(), 2);
+    assert_eq!(regions[0].base_address, 0x50_0000_0000);
+    assert_eq!(regions[0].size, 32);
+    assert_eq!(regions[1].kind, RegionType::Image);
+}
+
+/// llvm.dmp was written by LLVM yaml2obj from llvm.yaml (a writer independent of ours, also used
+/// to test the minidump support of LLDB): a system info, game.exe and KERNEL32.DLL with a
+/// CodeView record, a thread, a 32 bits memory list and a memory info list with a free region.
+#[test]
+fn read_sample_written_by_llvm() {
+    let dump = Minidump::load(sample("llvm.dmp")).unwrap();
+
+    assert_eq!(dump.main_module_base(), 0x7FF6_1234_0000);
+    assert_eq!(dump.modules.len(), 2);
+    assert_eq!(dump.modules[0].name, "game.exe");
+    assert_eq!(dump.modules[0].path, "C:\\Games\\Game\\game.exe");
+    assert_eq!(dump.modules[0].size, 0x3000);
+    assert_eq!(dump.modules[0].entry_point, 0x7FF6_1234_1230);
+    assert_eq!(dump.modules[1].name, "KERNEL32.DLL");
+    assert_eq!(dump.modules[1].size, 0xC2000);
+
+    assert_eq!(
+        dump.threads,
+        vec![MinidumpThread {
+            id: 0x3F0C,
+            teb: 0xC5_F8A2_E000,
+            stack_address: 0xC5_F8CF_F800,
+        }]
+    );
+
+    // The free region is skipped
+    let regions = dump.regions().unwrap();
+
+    assert_eq!(regions.len(), 6);
+    assert_eq!(regions[0].base_address, 0xC5_F8CF_F000);
+    assert_eq!(regions[2].state, RegionState::Reserved);
+    assert_eq!(
+        dump.query_region(0x7FF6_1234_1000).unwrap().protection,
+        Protection::READ | Protection::EXECUTE
+    );
+
+    let mlp: MultiLevelPointer = "\"game.exe\"+2000 -> 60".parse().unwrap();
+
+    assert_eq!(mlp.read::<u32>(&dump, 0).unwrap(), 800);
+    assert_eq!(mlp.read::<u32>(&dump, 4).unwrap(), 600);
+    assert_eq!(
+        memory::read::<[u8; 4]>(&dump, 0xC5_F8CF_F804 as *const _).unwrap(),
+        [0x44, 0x55, 0x66, 0x77]
+    );
+
+    // Committed but not dumped
+    let mut buffer = [0u8; 4];
+
+    assert!(dump.read_bytes(0x7FF6_1234_1000, &mut buffer).is_err());
+}
+
+#[test]
+fn write_and_load_mock_process() {
+    let mut process = MockProcess::new(0x40_0000);
+
+    process.add_module(Module {
+        name: String::from("game.exe"),
+        path: String::from("C:\\Game\\game.exe"),
+        base_address: 0x40_0000,
+        size: 2 * PAGE_SIZE,
+        entry_point: 0,
+    });
+    process.load(0x2000_0000, &[1, 2, 3, 4]);
+
+    let file = std::env::temp_dir().join(format!("wapi-mock-{}.dmp", std::process::id()));
+
+    minidump::write(&process, &[MinidumpThread::default()], &file).unwrap();
+    let dump = Minidump::load(&file);
+
+    std::fs::remove_file(&file).unwrap();
+
+    let dump = dump.unwrap();
+
+    assert_eq!(dump.modules().unwrap(), process.modules);
+    assert_eq!(dump.regions().unwrap(), process.regions().unwrap());
+    assert_eq!(dump.threads.len(), 1);
+    assert_eq!(
+        memory::read::<u32>(&dump, 0x2000_0000 as *const _).unwrap(),
+        0x0403_0201
+    );
+}
+
+#[test]
+fn load_rejects_other_files() {
+    let error = Minidump::load(sample("../minidump.rs")).err().unwrap();
+
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn load_rejects_crafted_files() {
+    let bytes = std::fs::read(sample("game.dmp")).unwrap();
+    let u32_at = |bytes: &[u8], offset: usize| {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap()) as usize
+    };
+
+    // Find the memory info list in the stream directory
+    let directory = u32_at(&bytes, 12);
+    let entry = (0..u32_at(&bytes, 8))
+        .map(|i| directory + i * 12)
+        .find(|entry| u32_at(&bytes, *entry) == 16)
+        .unwrap();
+    let info_list = u32_at(&bytes, entry + 8);
+
+    // Entries of 0 bytes (a huge count), a stream bigger than the file and a truncated file
+    let mut empty_entries = bytes.clone();
+    let mut huge_stream = bytes.clone();
+    let truncated = bytes[..bytes.len() / 2].to_vec();
+
+    empty_entries[info_list + 4..info_list + 8].copy_from_slice(&0u32.to_le_bytes());
+    empty_entries[info_list + 8..info_list + 16].copy_from_slice(&(1u64 << 40).to_le_bytes());
+    huge_stream[entry + 4..entry + 8].copy_from_slice(&u32::MAX.to_le_bytes());
+
+    let file = std::env::temp_dir().join(format!("wapi-crafted-{}.dmp", std::process::id()));
+
+    for crafted in [empty_entries, huge_stream, truncated] {
+        std::fs::write(&file, crafted).unwrap();
+
+        assert_eq!(
+            Minidump::load(&file).err().unwrap().kind(),
+            ErrorKind::InvalidData
+        );
+    }
+
+    std::fs::remove_file(&file).unwrap();
+}
@@ -0,0 +1,115 @@
+use wapi::backend::{MemoryBackend, Module, Protection, RegionState, RegionType};
+use wapi::mock::{MockProcess, PAGE_SIZE};
+
+#[test]
+fn read_and_write_across_pages() {
+    let process = MockProcess::default();
+
+    process.map(0x1000, 2 * PAGE_SIZE, Protection::READ | Protection::WRITE);
+
+    let bytes: Vec<u8> = (0..16).collect();
+
+    assert_eq!(process.write_bytes(0x1FF8, &bytes).unwrap(), 16);
+
+    let mut buffer = [0u8; 16];
+
+    assert_eq!(process.read_bytes(0x1FF8, &mut buffer).unwrap(), 16);
+    assert_eq!(buffer.to_vec(), bytes);
+}
+
+#[test]
+fn transfers_are_never_partial() {
+    let process = MockProcess::default();
+
+    process.map(0x1000, PAGE_SIZE, Protection::READ | Protection::WRITE);
+
+    assert!(process.write_bytes(0x1FFC, &[1; 8]).is_err());
+
+    let mut buffer = [0u8; 4];
+
+    process.read_bytes(0x1FFC, &mut buffer).unwrap();
+
+    assert_eq!(buffer, [0; 4]);
+}
+
+#[test]
+fn query_region_merges_pages_with_same_protection() {
+    let mut process = MockProcess::new(0x40_0000);
+
+    process.add_module(Module {
+        name: String::from("game.exe"),
+        path: String::from("C:\\game\\game.exe"),
+        base_address: 0x40_0000,
+        size: 2 * PAGE_SIZE,
+        entry_point: 0x40_1000,
+    });
+
+    process.map(
+        0x40_2000,
+        3 * PAGE_SIZE,
+        Protection::READ | Protection::WRITE,
+    );
+
+    let image = process.query_region(0x40_1234).unwrap();
+
+    assert_eq!(image.base_address, 0x40_0000);
+    assert_eq!(image.size, 2 * PAGE_SIZE);
+    assert_eq!(image.kind, RegionType::Image);
+    assert_eq!(image.protection, Protection::READ);
+
+    let data = process.query_region(0x40_3000).unwrap();
+
+    assert_eq!(data.base_address, 0x40_2000);
+    assert_eq!(data.size, 3 * PAGE_SIZE);
+    assert_eq!(data.kind, RegionType::Private);
+
+    let free = process.query_region(0x50_0000).unwrap();
+
+    assert_eq!(free.state, RegionState::Free);
+    assert_eq!(free.base_address, 0x40_5000);
+    assert!(!free.is_readable());
+}
+
+#[test]
+fn allocate_maps_new_pages() {
+    let process = MockProcess::default();
+
+    let first = process
+        .allocate(10, Protection::READ | Protection::WRITE)
+        .unwrap();
+    let second = process.allocate(PAGE_SIZE + 1, Protection::READ).unwrap();
+
+    assert_eq!(second, first + PAGE_SIZE);
+    assert_eq!(process.query_region(second).unwrap().size, 2 * PAGE_SIZE);
+    assert!(process.write_bytes(first, &[1, 2, 3]).is_ok());
+    assert!(process.write_bytes(second, &[1, 2, 3]).is_err());
+}
+
+#[test]
+fn regions_skip_free_space() {
+    let mut process = MockProcess::new(0x40_0000);
+
+    process.add_module(Module {
+        name: String::from("game.exe"),
+        path: String::from("C:\\game\\game.exe"),
+        base_address: 0x40_0000,
+        size: PAGE_SIZE,
+        entry_point: 0,
+    });
+
+    process.map(
+        0x80_0000,
+        2 * PAGE_SIZE,
+        Protection::READ | Protection::WRITE,
+    );
+    process.map(0x80_2000, PAGE_SIZE, Protection::NONE);
+
+    let regions: Vec<_> = wapi::memory::regions(&process).unwrap().collect();
+
+    assert_eq!(regions.len(), 3);
+    assert_eq!(regions[0].path.as_deref(), Some("C:\\game\\game.exe"));
+    assert_eq!(regions[1].base_address, 0x80_0000);
+    assert_eq!(regions[1].size, 2 * PAGE_SIZE);
+    assert!(regions[1].is_readable());
+    assert!(!regions[2].is_readable());
+}
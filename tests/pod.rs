@@ -0,0 +1,129 @@
+use std::ffi::c_void;
+use std::io::{Error, ErrorKind};
+
+use wapi::memory;
+use wapi::mock::MockProcess;
+use wapi::pod::{self, Pod, Validated};
+
+#[derive(Clone, Copy, Debug, PartialEq, Pod)]
+#[repr(C)]
+pub struct Vector3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Pod)]
+#[repr(C)]
+pub struct Player {
+    /// The position is a nested Pod struct
+    pub position: Vector3,
+    pub hp: i32,
+    pub name: [u8; 8],
+    pub inventory: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Pod)]
+#[repr(transparent)]
+struct Handle(u32);
+
+#[derive(Debug, PartialEq)]
+enum Team {
+    Red,
+    Blue,
+}
+
+impl Validated for Team {
+    type Raw = u32;
+
+    fn from_raw(raw: u32) -> Result<Team, Error> {
+        match raw {
+            1 => Ok(Team::Red),
+            2 => Ok(Team::Blue),
+            _ => Err(Error::new(ErrorKind::InvalidData, "invalid team")),
+        }
+    }
+
+    fn to_raw(&self) -> u32 {
+        match self {
+            Team::Red => 1,
+            Team::Blue => 2,
+        }
+    }
+}
+
+#[test]
+fn derived_pod_structs_are_read_and_written() {
+    let process = MockProcess::new(0);
+    let address = 0x2000_0000 as *const c_void;
+
+    let player = Player {
+        position: Vector3 {
+            x: 1.0,
+            y: 2.5,
+            z: -3.0,
+        },
+        hp: 800,
+        name: *b"leon\0\0\0\0",
+        inventory: 0x3000_0000,
+    };
+
+    process.load(0x2000_0000, pod::bytes_of(&player));
+
+    assert_eq!(memory::read::<Player>(&process, address).unwrap(), player);
+
+    memory::write(&process, 0x2000_0100 as *const c_void, Handle(7)).unwrap();
+
+    assert_eq!(
+        memory::read::<Handle>(&process, 0x2000_0100 as *const c_void).unwrap(),
+        Handle(7)
+    );
+}
+
+#[test]
+fn validated_values_reject_invalid_bits() {
+    let process = MockProcess::new(0);
+
+    process.load(0x2000_0000, &[1, 2]);
+    process.load(0x2000_0010, &0xD800u32.to_le_bytes());
+    process.load(0x2000_0020, &3u32.to_le_bytes());
+
+    assert!(memory::read_validated::<bool>(&process, 0x2000_0000 as *const c_void).unwrap());
+
+    let error = memory::read_validated::<bool>(&process, 0x2000_0001 as *const c_void);
+
+    assert_eq!(error.unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // A surrogate is not a valid char
+    let error = memory::read_validated::<char>(&process, 0x2000_0010 as *const c_void);
+
+    assert_eq!(error.unwrap_err().kind(), ErrorKind::InvalidData);
+
+    let error = memory::read_validated::<Team>(&process, 0x2000_0020 as *const c_void);
+
+    assert_eq!(error.unwrap_err().kind(), ErrorKind::InvalidData);
+
+    memory::write_validated(&process, 0x2000_0020 as *const c_void, &Team::Blue).unwrap();
+    memory::write_validated(&process, 0x2000_0010 as *const c_void, &'é').unwrap();
+
+    assert_eq!(
+        memory::read_validated::<Team>(&process, 0x2000_0020 as *const c_void).unwrap(),
+        Team::Blue
+    );
+    assert_eq!(
+        memory::read_validated::<char>(&process, 0x2000_0010 as *const c_void).unwrap(),
+        'é'
+    );
+}
+
+#[test]
+fn from_bytes_checks_the_size() {
+    assert_eq!(
+        pod::from_bytes::<u32>(&[0x78, 0x56, 0x34, 0x12]).unwrap(),
+        0x1234_5678
+    );
+    assert_eq!(
+        pod::from_bytes::<u32>(&[0; 3]).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+}
@@ -0,0 +1,109 @@
+use wapi::memory::{MultiLevelPointer, PointerBase};
+
+fn re2_player() -> MultiLevelPointer {
+    MultiLevelPointer {
+        base: PointerBase::Module(String::from("re2.exe")),
+        base_address: 0x091AD2C0,
+        offsets: vec![0x50, 0x10, 0x20, 0x230, 0x58],
+    }
+}
+
+#[test]
+fn parse_arrow_notation() {
+    let mlp: MultiLevelPointer = "\"re2.exe\"+091AD2C0 -> 50 -> 10 -> 20 -> 230 -> 58"
+        .parse()
+        .unwrap();
+
+    assert_eq!(mlp, re2_player());
+}
+
+#[test]
+fn parse_bracket_notation() {
+    let mlp: MultiLevelPointer = "[[[[[re2.exe+91AD2C0]+50]+10]+20]+230]+58".parse().unwrap();
+
+    assert_eq!(mlp, re2_player());
+}
+
+#[test]
+fn parse_bases() {
+    let absolute: MultiLevelPointer = "[0x7FF6000010]-8".parse().unwrap();
+    let main: MultiLevelPointer = "<main>+1234 -> 8".parse().unwrap();
+
+    assert_eq!(absolute.base, PointerBase::Absolute);
+    assert_eq!(absolute.base_address, 0x7FF6000010);
+    assert_eq!(absolute.offsets, vec![0usize.wrapping_sub(8)]);
+
+    assert_eq!(main.base, PointerBase::MainModule);
+    assert_eq!(main.base_address, 0x1234);
+    assert_eq!(main.offsets, vec![8]);
+}
+
+#[test]
+fn display_round_trips() {
+    let mlp = re2_player();
+
+    assert_eq!(
+        mlp.to_string(),
+        "\"re2.exe\"+91AD2C0 -> 50 -> 10 -> 20 -> 230 -> 58"
+    );
+    assert_eq!(
+        format!("{:#}", mlp),
+        "[[[[[\"re2.exe\"+91AD2C0]+50]+10]+20]+230]+58"
+    );
+
+    assert_eq!(mlp.to_string().parse::<MultiLevelPointer>().unwrap(), mlp);
+    assert_eq!(
+        format!("{:#}", mlp).parse::<MultiLevelPointer>().unwrap(),
+        mlp
+    );
+}
+
+#[test]
+fn parse_errors_point_at_offending_token() {
+    let error = "\"re2.exe\"+091AD2C0 -> 5G -> 10"
+        .parse::<MultiLevelPointer>()
+        .unwrap_err();
+
+    assert_eq!(error.column, 22);
+    assert_eq!(error.token, "5G");
+
+    let error = "[[game.exe+10]+8".parse::<MultiLevelPointer>().unwrap_err();
+
+    assert_eq!(error.column, 16);
+    assert_eq!(error.token, "");
+    assert_eq!(
+        error.to_string(),
+        "unexpected end of pointer path at column 16, expected \"]\""
+    );
+}
+
+#[test]
+fn parse_rejects_trailing_tokens() {
+    let error = "[a.exe+1]+2]".parse::<MultiLevelPointer>().unwrap_err();
+
+    assert_eq!(error.column, 11);
+    assert_eq!(error.token, "]");
+    assert_eq!(error.expected, "the end of the pointer path");
+
+    let error = "[[a.exe+1]+2]+3+4"
+        .parse::<MultiLevelPointer>()
+        .unwrap_err();
+
+    assert_eq!(error.column, 15);
+    assert_eq!(error.token, "+");
+}
+
+#[test]
+fn parse_negative_base_offset_after_unquoted_module() {
+    let unquoted: MultiLevelPointer = "game.exe-8 -> 10".parse().unwrap();
+    let quoted: MultiLevelPointer = "\"game.exe\"-8 -> 10".parse().unwrap();
+
+    assert_eq!(unquoted.base, PointerBase::Module(String::from("game.exe")));
+    assert_eq!(unquoted.base_address, 8usize.wrapping_neg());
+    assert_eq!(unquoted.offsets, vec![0x10]);
+    assert_eq!(unquoted, quoted);
+
+    let bracket: MultiLevelPointer = "[game.exe-8]+10".parse().unwrap();
+
+    assert_eq!(bracket, quoted);
+}
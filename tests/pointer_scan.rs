@@ -0,0 +1,159 @@
+use wapi::backend::Module;
+use wapi::memory::MultiLevelPointer;
+use wapi::mock::{MockProcess, PAGE_SIZE};
+use wapi::pointer_scan::{self, PointerMap, PointerScanOptions};
+
+/// Build a fake process with the chain "game.exe"+2000 -> 50 -> 10 -> 58 that reaches heap + 0x58,
+/// and a second static pointer at "game.exe"+2100 to the same object when stable_only is false.
+fn game_process(heap: usize, stable_only: bool) -> MockProcess {
+    let mut process = MockProcess::new(0x40_0000);
+
+    process.add_module(Module {
+        name: String::from("game.exe"),
+        path: String::from("C:\\Game\\game.exe"),
+        base_address: 0x40_0000,
+        size: 4 * PAGE_SIZE,
+        entry_point: 0x40_1000,
+    });
+
+    process.load(0x40_2000, &heap.to_le_bytes());
+    process.load(heap + 0x50, &(heap + 0x10_0000).to_le_bytes());
+    process.load(heap + 0x10_0000 + 0x10, &(heap + 0x20_0000).to_le_bytes());
+    process.load(heap + 0x20_0000 + 0x58, &800u32.to_le_bytes());
+
+    if !stable_only {
+        process.load(0x40_2100, &(heap + 0x20_0000).to_le_bytes());
+    }
+
+    process
+}
+
+#[test]
+fn build_keeps_only_pointers_to_readable_regions() {
+    let process = game_process(0x2000_0000, true);
+
+    process.load(0x2000_0100, &0xDEAD_0000usize.to_le_bytes());
+
+    let map = PointerMap::build(&process).unwrap();
+
+    assert_eq!(map.len(), 3);
+    assert_eq!(map.value_at(0x40_2000), Some(0x2000_0000));
+    assert_eq!(map.value_at(0x2000_0100), None);
+}
+
+#[test]
+fn scan_finds_static_paths() {
+    let process = game_process(0x2000_0000, false);
+
+    let paths = pointer_scan::scan(&process, 0x2020_0058, &PointerScanOptions::default()).unwrap();
+
+    let expected: Vec<MultiLevelPointer> = vec![
+        "\"game.exe\"+2100 -> 58".parse().unwrap(),
+        "\"game.exe\"+2000 -> 50 -> 10 -> 58".parse().unwrap(),
+    ];
+
+    assert_eq!(paths, expected);
+}
+
+#[test]
+fn scan_respects_limits() {
+    let process = game_process(0x2000_0000, false);
+
+    let short = PointerScanOptions {
+        max_depth: 2,
+        ..PointerScanOptions::default()
+    };
+
+    let near = PointerScanOptions {
+        max_offset: 0x40,
+        ..PointerScanOptions::default()
+    };
+
+    assert_eq!(
+        pointer_scan::scan(&process, 0x2020_0058, &short)
+            .unwrap()
+            .len(),
+        1
+    );
+    assert!(pointer_scan::scan(&process, 0x2020_0058, &near)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn revalidate_filters_unstable_paths() {
+    let first_run = game_process(0x2000_0000, false);
+    let second_run = game_process(0x3000_0000, true);
+
+    let paths =
+        pointer_scan::scan(&first_run, 0x2020_0058, &PointerScanOptions::default()).unwrap();
+
+    let map = PointerMap::build(&second_run).unwrap();
+    let stable = pointer_scan::revalidate(&map, &paths, 0x3020_0058);
+
+    assert_eq!(stable.len(), 1);
+    assert_eq!(stable[0].to_string(), "\"game.exe\"+2000 -> 50 -> 10 -> 58");
+    assert_eq!(stable[0].read::<u32>(&second_run, 0).unwrap(), 800);
+}
+
+#[test]
+fn paths_survive_save_and_load() {
+    let process = game_process(0x2000_0000, false);
+    let file = std::env::temp_dir().join(format!("wapi-paths-{}.txt", std::process::id()));
+
+    let paths = pointer_scan::scan(&process, 0x2020_0058, &PointerScanOptions::default()).unwrap();
+
+    pointer_scan::save_paths(&file, &paths).unwrap();
+    let loaded = pointer_scan::load_paths(&file).unwrap();
+
+    std::fs::remove_file(&file).unwrap();
+
+    assert_eq!(loaded, paths);
+}
+
+#[test]
+fn snapshots_survive_save_and_load() {
+    let process = game_process(0x2000_0000, false);
+    let file = std::env::temp_dir().join(format!("wapi-snapshot-{}.pmap", std::process::id()));
+
+    let map = PointerMap::build(&process).unwrap();
+
+    map.save(&file).unwrap();
+    let loaded = PointerMap::load(&file).unwrap();
+
+    std::fs::remove_file(&file).unwrap();
+
+    assert_eq!(loaded.pointers(), map.pointers());
+    assert_eq!(loaded.modules, map.modules);
+    assert_eq!(loaded.main_module_base, map.main_module_base);
+    assert_eq!(
+        loaded.find_paths(0x2020_0058, &PointerScanOptions::default()),
+        map.find_paths(0x2020_0058, &PointerScanOptions::default())
+    );
+}
+
+#[test]
+fn load_rejects_other_files() {
+    let file = std::env::temp_dir().join(format!("wapi-not-snapshot-{}.pmap", std::process::id()));
+
+    std::fs::write(&file, b"not a snapshot").unwrap();
+    let error = PointerMap::load(&file).err().unwrap();
+
+    std::fs::remove_file(&file).unwrap();
+
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn intersect_keeps_paths_that_survive_restarts() {
+    let first_run = PointerMap::build(&game_process(0x2000_0000, false)).unwrap();
+    let second_run = PointerMap::build(&game_process(0x3000_0000, true)).unwrap();
+
+    let paths = pointer_scan::intersect(
+        &[(&first_run, 0x2020_0058), (&second_run, 0x3020_0058)],
+        &PointerScanOptions::default(),
+    );
+
+    assert_eq!(paths.len(), 1);
+    assert_eq!(paths[0].to_string(), "\"game.exe\"+2000 -> 50 -> 10 -> 58");
+}
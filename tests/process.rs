@@ -0,0 +1,112 @@
+#![cfg(target_os = "linux")]
+
+use std::ffi::c_void;
+
+use wapi::backend::{MemoryBackend, RegionType};
+use wapi::dump::MemoryDump;
+use wapi::memory;
+use wapi::minidump::{self, Minidump};
+use wapi::process;
+
+static mut VALUE: u64 = 0x1122_3344_5566_7788;
+
+static DUMPED: u64 = 0x8877_6655_4433_2211;
+
+#[test]
+fn read_and_write_own_memory() {
+    let process = process::open(std::process::id()).unwrap();
+    let address = std::ptr::addr_of!(VALUE) as *const c_void;
+
+    assert_eq!(
+        memory::read::<u64>(&process, address).unwrap(),
+        0x1122_3344_5566_7788
+    );
+
+    memory::write::<u64>(&process, address, 42).unwrap();
+
+    assert_eq!(
+        unsafe { std::ptr::read_volatile(std::ptr::addr_of!(VALUE)) },
+        42
+    );
+    assert!(memory::read::<u64>(&process, 0x10 as *const c_void).is_err());
+}
+
+#[test]
+fn modules_contain_the_executable() {
+    let process = process::open(std::process::id()).unwrap();
+
+    let modules = process.modules().unwrap();
+    let executable = modules.iter().find(|m| m.name == process.name).unwrap();
+
+    assert_eq!(executable.base_address, process.main_module_base());
+    assert!(executable.contains(executable.entry_point));
+
+    let region = process.query_region(executable.entry_point).unwrap();
+
+    assert_eq!(region.kind, RegionType::Image);
+    assert!(region.protection.is_executable());
+}
+
+#[test]
+fn dump_own_memory() {
+    let process = process::open(std::process::id()).unwrap();
+    let file = std::env::temp_dir().join(format!("wapi-self-{}.dump", std::process::id()));
+
+    memory::dump(&process, &file).unwrap();
+    let dump = MemoryDump::load(&file);
+
+    std::fs::remove_file(&file).unwrap();
+
+    let dump = dump.unwrap();
+    let address = std::ptr::addr_of!(DUMPED) as *const c_void;
+
+    assert_eq!(dump.metadata.pid, process.pid);
+    assert_eq!(dump.metadata.exe_hash, process::get_hash(&process).unwrap());
+    assert_eq!(dump.main_module_base(), process.main_module_base());
+    assert_eq!(
+        memory::read::<u64>(&dump, address).unwrap(),
+        0x8877_6655_4433_2211
+    );
+}
+
+#[test]
+fn minidump_own_process() {
+    let process = process::open(std::process::id()).unwrap();
+    let file = std::env::temp_dir().join(format!("wapi-self-{}.dmp", std::process::id()));
+
+    minidump::write_process(&process, &file).unwrap();
+    let dump = Minidump::load(&file);
+
+    std::fs::remove_file(&file).unwrap();
+
+    let dump = dump.unwrap();
+    let address = std::ptr::addr_of!(DUMPED) as *const c_void;
+
+    assert!(dump.threads.iter().any(|thread| thread.id == process.pid));
+    assert_eq!(
+        memory::read::<u64>(&dump, address).unwrap(),
+        0x8877_6655_4433_2211
+    );
+}
+
+#[test]
+fn read_many_reports_each_range() {
+    let process = process::open(std::process::id()).unwrap();
+    let address = std::ptr::addr_of!(DUMPED) as usize;
+
+    let mut value = [0u8; 8];
+    let mut invalid = [0u8; 8];
+    let mut second = [0u8; 4];
+
+    let results = process.read_many(&mut [
+        (address, &mut value[..]),
+        (0x10, &mut invalid[..]),
+        (address + 4, &mut second[..]),
+    ]);
+
+    assert_eq!(results[0].as_ref().unwrap(), &8);
+    assert!(results[1].is_err());
+    assert_eq!(results[2].as_ref().unwrap(), &4);
+    assert_eq!(u64::from_ne_bytes(value), 0x8877_6655_4433_2211);
+    assert_eq!(u32::from_ne_bytes(second), 0x8877_6655);
+}
@@ -0,0 +1,133 @@
+use std::io::ErrorKind;
+
+use wapi::memory::{MultiLevelPointer, PointerBase};
+use wapi::mock::MockProcess;
+use wapi::pod::Pod;
+use wapi::remote_struct::RemoteStruct;
+
+#[derive(Clone, Copy, Debug, PartialEq, Pod)]
+#[repr(C)]
+pub struct Vector3 {
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+#[derive(Debug, PartialEq, RemoteStruct)]
+pub struct HitPoint {
+    #[offset(0x54)]
+    max_hp: i32,
+
+    #[offset(0x58)]
+    hp: i32,
+}
+
+#[derive(Debug, PartialEq, RemoteStruct)]
+pub struct PlayerCondition {
+    #[offset(0x8)]
+    pub position: Vector3,
+
+    #[offset(0x230)]
+    #[pointer]
+    #[nested]
+    pub hit_point: HitPoint,
+
+    /// The level is at [[condition+0x40]+0x10]+0x4
+    #[offset(0x40)]
+    #[pointer(0x10, 0x4)]
+    pub level: u16,
+}
+
+/// Build a fake process with the player condition of re2.exe at 0x2300_0000.
+fn player_process() -> MockProcess {
+    let process = MockProcess::new(0x1_4000_0000);
+
+    process.load(0x1_4000_0000 + 0x091AD2C0, &0x2000_0000usize.to_le_bytes());
+    process.load(0x2000_0000 + 0x50, &0x2100_0000usize.to_le_bytes());
+    process.load(0x2100_0000 + 0x10, &0x2200_0000usize.to_le_bytes());
+    process.load(0x2200_0000 + 0x20, &0x2300_0000usize.to_le_bytes());
+
+    for (i, coordinate) in [1.5f32, -2.0, 8.25].iter().enumerate() {
+        process.load(0x2300_0008 + i * 4, &coordinate.to_le_bytes());
+    }
+
+    process.load(0x2300_0000 + 0x230, &0x2400_0000usize.to_le_bytes());
+    process.load(0x2400_0000 + 0x54, &1200i32.to_le_bytes());
+    process.load(0x2400_0000 + 0x58, &800i32.to_le_bytes());
+
+    process.load(0x2300_0000 + 0x40, &0x2500_0000usize.to_le_bytes());
+    process.load(0x2500_0000 + 0x10, &0x2600_0000usize.to_le_bytes());
+    process.load(0x2600_0000 + 0x4, &12u16.to_le_bytes());
+
+    process
+}
+
+#[test]
+fn read_from_reads_every_field() {
+    let process = player_process();
+
+    let condition_ptr = MultiLevelPointer {
+        base: PointerBase::MainModule,
+        base_address: 0x091AD2C0,
+        offsets: vec![0x50, 0x10, 0x20],
+    };
+
+    let condition_address: usize = condition_ptr.read(&process, 0).unwrap();
+
+    assert_eq!(
+        PlayerCondition::read_from(&process, condition_address).unwrap(),
+        PlayerCondition {
+            position: Vector3 {
+                x: 1.5,
+                y: -2.0,
+                z: 8.25,
+            },
+            hit_point: HitPoint {
+                max_hp: 1200,
+                hp: 800,
+            },
+            level: 12,
+        }
+    );
+}
+
+#[test]
+fn accessors_read_and_write_a_single_field() {
+    let process = player_process();
+
+    assert_eq!(
+        PlayerCondition::hit_point_address(&process, 0x2300_0000).unwrap(),
+        0x2400_0000
+    );
+    assert_eq!(
+        PlayerCondition::level_address(&process, 0x2300_0000).unwrap(),
+        0x2600_0004
+    );
+    assert_eq!(HitPoint::read_hp(&process, 0x2400_0000).unwrap(), 800);
+
+    HitPoint::write_hp(&process, 0x2400_0000, 1200).unwrap();
+    PlayerCondition::write_level(&process, 0x2300_0000, 13).unwrap();
+
+    let condition = PlayerCondition::read_from(&process, 0x2300_0000).unwrap();
+
+    assert_eq!(condition.hit_point.hp, 1200);
+    assert_eq!(condition.level, 13);
+}
+
+#[test]
+fn read_from_fails_on_broken_pointer() {
+    let process = player_process();
+
+    process.unmap(0x2400_0000, 0x1000);
+
+    assert_eq!(
+        PlayerCondition::read_from(&process, 0x2300_0000)
+            .unwrap_err()
+            .kind(),
+        ErrorKind::NotFound
+    );
+    assert_eq!(
+        PlayerCondition::read_level(&process, 0x2300_0000).unwrap(),
+        12
+    );
+}
@@ -0,0 +1,165 @@
+use std::ffi::c_void;
+
+use wapi::backend::Protection;
+use wapi::memory;
+use wapi::mock::{MockProcess, PAGE_SIZE};
+use wapi::scan::{self, ScanOptions, ScanPredicate};
+
+fn write_i32(process: &MockProcess, address: usize, value: i32) {
+    memory::write::<i32>(process, address as *const c_void, value).unwrap();
+}
+
+#[test]
+fn first_scan_finds_aligned_values_in_writable_regions() {
+    let process = MockProcess::default();
+
+    process.map(0x10000, 4 * PAGE_SIZE, Protection::READ | Protection::WRITE);
+    process.map(0x20000, PAGE_SIZE, Protection::READ);
+
+    write_i32(&process, 0x10008, 800);
+    write_i32(&process, 0x13FFC, 800);
+    process.load(0x20000, &800i32.to_ne_bytes());
+
+    // Unaligned value, only found with an alignment of 1
+    process.load(0x11001, &800i32.to_ne_bytes());
+
+    let found = scan::first_scan(&process, 800i32, &ScanOptions::default()).unwrap();
+
+    assert_eq!(
+        found.addresses().collect::<Vec<_>>(),
+        vec![0x10008, 0x13FFC]
+    );
+
+    let options = ScanOptions {
+        alignment: Some(1),
+        writable_only: false,
+    };
+
+    let found = scan::first_scan(&process, 800i32, &options).unwrap();
+
+    assert_eq!(
+        found.addresses().collect::<Vec<_>>(),
+        vec![0x10008, 0x11001, 0x13FFC, 0x20000]
+    );
+}
+
+#[test]
+fn next_scan_narrows_candidates() {
+    let process = MockProcess::default();
+
+    process.map(0x10000, PAGE_SIZE, Protection::READ | Protection::WRITE);
+
+    for i in 0..8 {
+        write_i32(&process, 0x10000 + i * 0x100, 100);
+    }
+
+    let mut found = scan::first_scan(&process, 100i32, &ScanOptions::default()).unwrap();
+
+    assert_eq!(found.len(), 8);
+
+    write_i32(&process, 0x10100, 90);
+    write_i32(&process, 0x10200, 120);
+    write_i32(&process, 0x10300, 150);
+
+    found.next_scan(&process, ScanPredicate::Unchanged);
+
+    assert_eq!(found.len(), 5);
+
+    let mut found = scan::first_scan(&process, 100i32, &ScanOptions::default()).unwrap();
+
+    write_i32(&process, 0x10000, 110);
+    write_i32(&process, 0x10400, 130);
+    write_i32(&process, 0x10500, 95);
+
+    found.next_scan(&process, ScanPredicate::Changed);
+
+    assert_eq!(found.len(), 3);
+
+    found.next_scan(&process, ScanPredicate::Unchanged);
+
+    assert_eq!(found.len(), 3);
+
+    write_i32(&process, 0x10000, 111);
+    write_i32(&process, 0x10400, 129);
+
+    let mut increased = scan::first_scan(&process, 111i32, &ScanOptions::default()).unwrap();
+
+    found.next_scan(&process, ScanPredicate::Decreased);
+
+    assert_eq!(found.addresses().collect::<Vec<_>>(), vec![0x10400]);
+
+    write_i32(&process, 0x10000, 112);
+
+    increased.next_scan(&process, ScanPredicate::Increased);
+
+    assert_eq!(
+        increased.results().collect::<Vec<_>>(),
+        vec![(0x10000, 112)]
+    );
+}
+
+#[test]
+fn next_scan_compares_with_values() {
+    let process = MockProcess::default();
+
+    process.map(0x10000, PAGE_SIZE, Protection::READ | Protection::WRITE);
+    process.load(0x10000, &1.5f32.to_ne_bytes());
+    process.load(0x10010, &1.5f32.to_ne_bytes());
+    process.load(0x10020, &1.5f32.to_ne_bytes());
+
+    let mut found = scan::first_scan(&process, 1.5f32, &ScanOptions::default()).unwrap();
+
+    process.load(0x10000, &2.0f32.to_ne_bytes());
+    process.load(0x10010, &8.0f32.to_ne_bytes());
+
+    found.next_scan(&process, ScanPredicate::Between(1.0, 5.0));
+
+    assert_eq!(
+        found.addresses().collect::<Vec<_>>(),
+        vec![0x10000, 0x10020]
+    );
+
+    found.next_scan(&process, ScanPredicate::EqualTo(2.0));
+
+    assert_eq!(found.addresses().collect::<Vec<_>>(), vec![0x10000]);
+}
+
+#[test]
+fn next_scan_drops_unreadable_candidates() {
+    let process = MockProcess::default();
+
+    process.map(0x10000, PAGE_SIZE, Protection::READ | Protection::WRITE);
+    process.map(0x30000, PAGE_SIZE, Protection::READ | Protection::WRITE);
+    process.load(0x10000, &7u64.to_ne_bytes());
+    process.load(0x30000, &7u64.to_ne_bytes());
+
+    let mut found = scan::first_scan(&process, 7u64, &ScanOptions::default()).unwrap();
+
+    process.unmap(0x30000, PAGE_SIZE);
+
+    found.next_scan(&process, ScanPredicate::Unchanged);
+
+    assert_eq!(found.addresses().collect::<Vec<_>>(), vec![0x10000]);
+}
+
+#[test]
+fn next_scan_keeps_readable_candidates_of_a_partially_unmapped_chunk() {
+    let process = MockProcess::default();
+
+    process.map(0x10000, 3 * PAGE_SIZE, Protection::READ | Protection::WRITE);
+    process.load(0x10000, &7u64.to_ne_bytes());
+    process.load(0x11000, &7u64.to_ne_bytes());
+    process.load(0x12000, &7u64.to_ne_bytes());
+
+    let mut found = scan::first_scan(&process, 7u64, &ScanOptions::default()).unwrap();
+
+    // The page in the middle of the chunk is unmapped
+    process.unmap(0x11000, PAGE_SIZE);
+
+    found.next_scan(&process, ScanPredicate::Unchanged);
+
+    assert_eq!(
+        found.addresses().collect::<Vec<_>>(),
+        vec![0x10000, 0x12000]
+    );
+}
@@ -0,0 +1,145 @@
+use wapi::backend::{Module, Protection};
+use wapi::mock::{MockProcess, PAGE_SIZE};
+use wapi::signature::{self, Pattern, Resolution, Signature};
+
+fn game_process() -> MockProcess {
+    let mut process = MockProcess::new(0x40_0000);
+
+    process.add_module(Module {
+        name: String::from("game.exe"),
+        path: String::from("C:\\Game\\game.exe"),
+        base_address: 0x40_0000,
+        size: 4 * PAGE_SIZE,
+        entry_point: 0x40_1000,
+    });
+
+    process
+}
+
+#[test]
+fn parse_accepts_ida_and_x64dbg_wildcards() {
+    let pattern = Pattern::parse("48 8b 05 ? ?? 4? ?C").unwrap();
+
+    assert_eq!(pattern.len(), 7);
+    assert_eq!(pattern.to_string(), "48 8B 05 ?? ?? 4? ?C");
+    assert!(pattern.matches(&[0x48, 0x8B, 0x05, 0x01, 0x02, 0x4F, 0xAC]));
+    assert!(!pattern.matches(&[0x48, 0x8B, 0x05, 0x01, 0x02, 0x5F, 0xAC]));
+    assert!(!pattern.matches(&[0x48, 0x8B, 0x05, 0x01, 0x02, 0x4F, 0xAD]));
+}
+
+#[test]
+fn parse_rejects_invalid_tokens() {
+    assert!(Pattern::parse("").is_err());
+    assert!(Pattern::parse("48 8G").is_err());
+    assert!(Pattern::parse("48 123").is_err());
+}
+
+#[test]
+fn find_all_returns_every_match() {
+    let pattern = Pattern::parse("?? 01 02 ?? 04").unwrap();
+
+    let haystack = [
+        0x01, 0x02, 0xFF, 0x01, 0x02, 0x03, 0x04, 0x00, 0x01, 0x02, 0x00, 0x04,
+    ];
+
+    assert_eq!(pattern.find_all(&haystack), vec![2, 7]);
+
+    let wildcards = Pattern::parse("?? ??").unwrap();
+
+    assert_eq!(wildcards.find_all(&[1, 2, 3]), vec![0, 1]);
+}
+
+#[test]
+fn find_all_returns_overlapping_matches() {
+    let pattern = Pattern::parse("90 90 ?? C3").unwrap();
+
+    assert_eq!(pattern.find_all(&[0x90, 0x90, 0x90, 0x00, 0xC3]), vec![1]);
+
+    // A signature in INT3 padding
+    let padding = Pattern::parse("CC CC").unwrap();
+
+    assert_eq!(padding.find_all(&[0xCC, 0xCC, 0xCC, 0xCC]), vec![0, 1, 2]);
+}
+
+#[test]
+fn aob_scan_searches_the_module() {
+    let process = game_process();
+
+    let code = [0x48, 0x8B, 0x05, 0x10, 0x20, 0x30, 0x40, 0x48, 0x85, 0xC0];
+
+    process.load(0x40_1234, &code);
+    process.load(0x40_3FFC, &code);
+
+    // The same bytes outside of the module are ignored
+    process.load(0x90_0000, &code);
+
+    let matches = signature::aob_scan(&process, "GAME.EXE", "48 8B 05 ?? ?? ?? ?? 48 85 C0");
+
+    assert_eq!(matches.unwrap(), vec![0x40_1234]);
+    assert!(signature::aob_scan(&process, "engine.dll", "48 8B").is_err());
+}
+
+#[test]
+fn find_pattern_searches_region_list() {
+    let process = game_process();
+
+    process.map(0x90_0000, 2 * PAGE_SIZE, Protection::READ);
+    process.load(0x90_0FFE, &[0xDE, 0xAD, 0xBE, 0xEF]);
+
+    let pattern = Pattern::parse("DE AD BE EF").unwrap();
+    let regions: Vec<_> = wapi::memory::regions(&process).unwrap().collect();
+
+    let matches = signature::find_pattern(&process, &regions, &pattern).unwrap();
+
+    assert_eq!(matches, vec![0x90_0FFE]);
+}
+
+#[test]
+fn resolve_relative_computes_rip_relative_targets() {
+    let process = game_process();
+
+    // mov rax, [rip+0x2000] at 0x401000 => 0x401007 + 0x2000
+    process.load(0x40_1000, &[0x48, 0x8B, 0x05, 0x00, 0x20, 0x00, 0x00]);
+
+    // call -0x100 at 0x401100 => 0x401105 - 0x100
+    process.load(0x40_1100, &[0xE8, 0x00, 0xFF, 0xFF, 0xFF]);
+
+    assert_eq!(
+        signature::resolve_relative(&process, 0x40_1000, 3, 7).unwrap(),
+        0x40_3007
+    );
+    assert_eq!(
+        signature::resolve_relative(&process, 0x40_1100, 1, 5).unwrap(),
+        0x40_1005
+    );
+}
+
+#[test]
+fn signature_defines_a_pointer_chain() {
+    let process = game_process();
+
+    // test rcx, rcx; mov rax, [rip+0xFF6] => global pointer at 0x40100A + 0xFF6
+    process.load(
+        0x40_1000,
+        &[0x48, 0x85, 0xC9, 0x48, 0x8B, 0x05, 0xF6, 0x0F, 0x00, 0x00],
+    );
+    process.load(0x40_2000, &0x10_0000usize.to_le_bytes());
+    process.load(0x10_0018, &42u32.to_le_bytes());
+
+    let definition = Signature::new(
+        "game.exe",
+        "48 85 C9 48 8B 05 ?? ?? ?? ??",
+        Resolution::Relative {
+            instruction_offset: 3,
+            operand_offset: 3,
+            instruction_length: 7,
+        },
+    )
+    .unwrap();
+
+    assert_eq!(definition.resolve(&process).unwrap(), 0x40_2000);
+
+    let pointer = definition.to_multi_level_pointer(vec![0x18]);
+
+    assert_eq!(pointer.read::<u32>(&process, 0).unwrap(), 42);
+}
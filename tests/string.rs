@@ -0,0 +1,213 @@
+use std::io::ErrorKind;
+use std::mem::size_of;
+
+use wapi::memory::{MultiLevelPointer, PointerBase};
+use wapi::mock::{MockProcess, PAGE_SIZE};
+use wapi::string::{self, StringLayout};
+
+/// Encode a string as little-endian UTF-16 bytes.
+fn utf16(value: &str) -> Vec<u8> {
+    value.encode_utf16().flat_map(u16::to_le_bytes).collect()
+}
+
+/// Write a MSVC std::basic_string object at the specified address.
+fn load_msvc_string(
+    process: &MockProcess,
+    address: usize,
+    buffer: &[u8],
+    size: usize,
+    capacity: usize,
+) {
+    process.load(address, buffer);
+    process.load(address + 16, &size.to_le_bytes());
+    process.load(address + 16 + size_of::<usize>(), &capacity.to_le_bytes());
+}
+
+#[test]
+fn null_terminated_strings_are_read_up_to_the_null() {
+    let process = MockProcess::new(0);
+
+    process.load(0x2000_0000, b"Leon S. Kennedy\0garbage");
+    process.load(0x2000_0100, &[0x43, 0x6C, 0x61, 0x69, 0x72, 0x65, 0xE9, 0]);
+    process.load(0x2000_0200, &utf16("Ada Wong\0"));
+
+    assert_eq!(
+        string::read_c_string(&process, 0x2000_0000, 64).unwrap(),
+        "Leon S. Kennedy"
+    );
+    assert_eq!(
+        string::read_c_string(&process, 0x2000_0000, 15).unwrap(),
+        "Leon S. Kennedy"
+    );
+    assert_eq!(
+        string::read_c_string(&process, 0x2000_0000, 14)
+            .unwrap_err()
+            .kind(),
+        ErrorKind::InvalidData
+    );
+
+    // An ANSI string (Windows-1252) is not UTF-8 but its bytes can be read
+    assert_eq!(
+        string::read_c_string(&process, 0x2000_0100, 64)
+            .unwrap_err()
+            .kind(),
+        ErrorKind::InvalidData
+    );
+    assert_eq!(
+        string::read_c_bytes(&process, 0x2000_0100, 64).unwrap(),
+        b"Claire\xE9"
+    );
+
+    assert_eq!(
+        string::read_utf16_string(&process, 0x2000_0200, 64).unwrap(),
+        "Ada Wong"
+    );
+}
+
+#[test]
+fn null_terminated_strings_can_end_at_the_end_of_a_region() {
+    let process = MockProcess::new(0);
+    let address = 0x2000_0000 + PAGE_SIZE - 4;
+
+    process.load(address, b"Ben\0");
+
+    assert_eq!(
+        string::read_c_string(&process, address, 256).unwrap(),
+        "Ben"
+    );
+
+    process.load(address, b"Bens");
+
+    assert_eq!(
+        string::read_c_string(&process, address, 256)
+            .unwrap_err()
+            .kind(),
+        ErrorKind::NotFound
+    );
+}
+
+#[test]
+fn prefixed_strings_are_read() {
+    let process = MockProcess::new(0);
+
+    process.load(0x2000_0000, &[5, 0, b'S', b'h', b'e', b'r', b'r', b'y']);
+
+    assert_eq!(
+        string::read_prefixed_string(&process, 0x2000_0000, 2, 16).unwrap(),
+        "Sherr"
+    );
+    assert_eq!(
+        string::read_prefixed_string(&process, 0x2000_0000, 2, 4)
+            .unwrap_err()
+            .kind(),
+        ErrorKind::InvalidData
+    );
+    assert_eq!(
+        string::read_prefixed_string(&process, 0x2000_0000, 3, 16)
+            .unwrap_err()
+            .kind(),
+        ErrorKind::InvalidInput
+    );
+}
+
+#[test]
+fn msvc_strings_are_read_inline_or_from_the_heap() {
+    let process = MockProcess::new(0);
+
+    // Short string stored in the object
+    load_msvc_string(&process, 0x2000_0000, b"Marvin\0", 6, 15);
+
+    // Long string stored on the heap
+    let long = "Raccoon City Police Department";
+
+    process.load(0x2100_0000, long.as_bytes());
+    load_msvc_string(
+        &process,
+        0x2000_0100,
+        &0x2100_0000usize.to_le_bytes(),
+        30,
+        31,
+    );
+
+    // Wide strings have an inline buffer of 8 characters
+    process.load(0x2100_1000, &utf16("Mr. X"));
+    load_msvc_string(&process, 0x2000_0200, &utf16("Kendo"), 5, 7);
+    load_msvc_string(&process, 0x2000_0300, &0x2100_1000usize.to_le_bytes(), 5, 8);
+
+    assert_eq!(
+        string::read_msvc_string(&process, 0x2000_0000, 64).unwrap(),
+        "Marvin"
+    );
+    assert_eq!(
+        string::read_msvc_string(&process, 0x2000_0100, 64).unwrap(),
+        long
+    );
+    assert_eq!(
+        string::read_msvc_wstring(&process, 0x2000_0200, 64).unwrap(),
+        "Kendo"
+    );
+    assert_eq!(
+        string::read_msvc_wstring(&process, 0x2000_0300, 64).unwrap(),
+        "Mr. X"
+    );
+
+    // The size is more than the capacity
+    load_msvc_string(&process, 0x2000_0400, b"", 40, 15);
+
+    assert_eq!(
+        string::read_msvc_string(&process, 0x2000_0400, 64)
+            .unwrap_err()
+            .kind(),
+        ErrorKind::InvalidData
+    );
+}
+
+#[test]
+fn libstdcpp_strings_are_read() {
+    let process = MockProcess::new(0);
+    let local_buffer = 0x2000_0000 + 2 * size_of::<usize>();
+
+    process.load(0x2000_0000, &local_buffer.to_le_bytes());
+    process.load(0x2000_0000 + size_of::<usize>(), &5usize.to_le_bytes());
+    process.load(local_buffer, b"Annette\0");
+
+    assert_eq!(
+        string::read_libstdcpp_string(&process, 0x2000_0000, 64).unwrap(),
+        "Annet"
+    );
+}
+
+#[test]
+fn strings_are_read_at_the_end_of_multi_level_pointers() {
+    let process = MockProcess::new(0x1_4000_0000);
+
+    process.load(0x1_4000_0000 + 0x100, &0x2000_0000usize.to_le_bytes());
+    process.load(0x2000_0000 + 0x18, &0x2100_0000usize.to_le_bytes());
+    process.load(0x2100_0000, b"Umbrella\0");
+    load_msvc_string(&process, 0x2000_0040, b"Tyrant\0", 6, 15);
+
+    let player_ptr = MultiLevelPointer {
+        base: PointerBase::MainModule,
+        base_address: 0x100,
+        offsets: vec![],
+    };
+
+    let name_ptr = MultiLevelPointer {
+        base: PointerBase::MainModule,
+        base_address: 0x100,
+        offsets: vec![0x18, 0x0],
+    };
+
+    assert_eq!(
+        name_ptr
+            .read_string(&process, 0, StringLayout::CString, 64)
+            .unwrap(),
+        "Umbrella"
+    );
+    assert_eq!(
+        player_ptr
+            .read_string(&process, 0x40, StringLayout::MsvcString, 64)
+            .unwrap(),
+        "Tyrant"
+    );
+}
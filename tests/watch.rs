@@ -0,0 +1,181 @@
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use wapi::memory::{MultiLevelPointer, PointerBase};
+use wapi::mock::MockProcess;
+use wapi::watch::{WatchTarget, Watcher};
+
+static INTERVAL: Duration = Duration::from_millis(5);
+static TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Build a fake process with a pointer at base+0x100 to a struct with the max HP (1200) at +0x54
+/// and the HP (800) at +0x58.
+fn game_process() -> Arc<MockProcess> {
+    let process = MockProcess::new(0x40_0000);
+
+    process.load(0x40_0000 + 0x100, &0x2000_0000usize.to_le_bytes());
+    process.load(0x2000_0000 + 0x54, &1200i32.to_le_bytes());
+    process.load(0x2000_0000 + 0x58, &800i32.to_le_bytes());
+
+    Arc::new(process)
+}
+
+#[test]
+fn watch_sends_the_changes_only() {
+    let process = game_process();
+    let watcher = Watcher::new(process.clone());
+    let address = 0x2000_0000 + 0x58;
+
+    let guard = watcher.watch(WatchTarget::Address(address), 4, INTERVAL);
+
+    thread::sleep(INTERVAL * 4);
+
+    assert!(watcher.events.try_recv().is_err());
+
+    process.load(address, &750i32.to_le_bytes());
+
+    let event = watcher.events.recv_timeout(TIMEOUT).unwrap();
+
+    assert_eq!(event.id, guard.id);
+    assert_eq!(event.address, address);
+    assert_eq!(event.old_value::<i32>().unwrap(), 800);
+    assert_eq!(event.new_value::<i32>().unwrap(), 750);
+
+    thread::sleep(INTERVAL * 4);
+
+    assert!(watcher.events.try_recv().is_err());
+}
+
+#[test]
+fn watch_with_calls_the_callback() {
+    let process = game_process();
+    let watcher = Watcher::new(process.clone());
+    let (sender, changes) = mpsc::channel();
+
+    let _hp_guard = watcher.watch_with(
+        WatchTarget::Address(0x2000_0000 + 0x58),
+        4,
+        INTERVAL,
+        move |event| sender.send(event.new_value::<i32>().unwrap()).unwrap(),
+    );
+    let _max_hp_guard = watcher.watch(WatchTarget::Address(0x2000_0000 + 0x54), 4, INTERVAL);
+
+    thread::sleep(INTERVAL * 4);
+
+    process.load(0x2000_0000 + 0x54, &1500i32.to_le_bytes());
+    process.load(0x2000_0000 + 0x58, &700i32.to_le_bytes());
+
+    // Each change goes to its own sink
+    assert_eq!(changes.recv_timeout(TIMEOUT).unwrap(), 700);
+    assert_eq!(
+        watcher
+            .events
+            .recv_timeout(TIMEOUT)
+            .unwrap()
+            .new_value::<i32>()
+            .unwrap(),
+        1500
+    );
+}
+
+#[test]
+fn watch_follows_the_pointer_again_on_every_read() {
+    let process = game_process();
+    let watcher = Watcher::new(process.clone());
+
+    let hp_ptr = MultiLevelPointer {
+        base: PointerBase::MainModule,
+        base_address: 0x100,
+        offsets: vec![0x58],
+    };
+
+    let _guard = watcher.watch(WatchTarget::Pointer(hp_ptr), 4, INTERVAL);
+
+    thread::sleep(INTERVAL * 4);
+
+    // The struct is reallocated: its value is not compared with the old struct
+    process.load(0x3000_0000 + 0x58, &10i32.to_le_bytes());
+    process.load(0x40_0000 + 0x100, &0x3000_0000usize.to_le_bytes());
+
+    thread::sleep(INTERVAL * 4);
+
+    assert!(watcher.events.try_recv().is_err());
+
+    process.load(0x3000_0000 + 0x58, &20i32.to_le_bytes());
+
+    let event = watcher.events.recv_timeout(TIMEOUT).unwrap();
+
+    assert_eq!(event.address, 0x3000_0000 + 0x58);
+    assert_eq!(event.old_value::<i32>().unwrap(), 10);
+    assert_eq!(event.new_value::<i32>().unwrap(), 20);
+}
+
+#[test]
+fn dropping_the_guard_stops_the_events() {
+    let process = game_process();
+    let watcher = Watcher::new(process.clone());
+    let address = 0x2000_0000 + 0x58;
+
+    let guard = watcher.watch(WatchTarget::Address(address), 4, INTERVAL);
+
+    thread::sleep(INTERVAL * 4);
+    drop(guard);
+
+    process.load(address, &10i32.to_le_bytes());
+
+    thread::sleep(INTERVAL * 4);
+
+    assert!(watcher.events.try_recv().is_err());
+}
+
+#[test]
+fn unreadable_value_is_reported_once() {
+    let process = game_process();
+    let watcher = Watcher::new(process.clone());
+
+    let guard = watcher.watch(WatchTarget::Address(0x5000_0000), 4, INTERVAL);
+
+    let error = watcher.errors.recv_timeout(TIMEOUT).unwrap();
+
+    assert_eq!(error.id, guard.id);
+    assert_eq!(error.target, WatchTarget::Address(0x5000_0000));
+
+    thread::sleep(INTERVAL * 4);
+
+    assert!(watcher.errors.try_recv().is_err());
+    assert!(watcher.events.try_recv().is_err());
+}
+
+#[test]
+fn callback_can_drop_guards() {
+    let process = game_process();
+    let watcher = Watcher::new(process.clone());
+    let (sender, changes) = mpsc::channel();
+
+    let mut max_hp_guard =
+        Some(watcher.watch(WatchTarget::Address(0x2000_0000 + 0x54), 4, INTERVAL));
+
+    let _hp_guard = watcher.watch_with(
+        WatchTarget::Address(0x2000_0000 + 0x58),
+        4,
+        INTERVAL,
+        move |event| {
+            max_hp_guard.take();
+            sender.send(event.new_value::<i32>().unwrap()).unwrap()
+        },
+    );
+
+    thread::sleep(INTERVAL * 4);
+
+    process.load(0x2000_0000 + 0x58, &700i32.to_le_bytes());
+
+    assert_eq!(changes.recv_timeout(TIMEOUT).unwrap(), 700);
+
+    process.load(0x2000_0000 + 0x54, &1500i32.to_le_bytes());
+    process.load(0x2000_0000 + 0x58, &600i32.to_le_bytes());
+
+    assert_eq!(changes.recv_timeout(TIMEOUT).unwrap(), 600);
+    assert!(watcher.events.try_recv().is_err());
+}